@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{KisaraError, KisaraResult},
+    torrent_adapters::TorrentInfo,
+    utils::release_name::SOURCES,
+};
+
+/// Release preferences of an anime, applied on top of the ranking profile whenever its
+/// episodes are searched.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AnimePreference {
+    pub anime_id: i32,
+    /// Release group, compared case-insensitively.
+    pub release_group: Option<String>,
+    /// Vertical resolution, e.g. `1080`.
+    pub resolution: Option<u32>,
+    /// Release source to favour, one of [`SOURCES`] like
+    /// [`ReleaseInfo::source`](crate::utils::release_name::ReleaseInfo::source).
+    pub source: Option<String>,
+    /// Only accept releases of `release_group`. When no group is set yet, the group of the
+    /// first downloaded episode becomes it, so the whole season comes from one group.
+    pub lock_group: bool,
+}
+
+impl AnimePreference {
+    pub fn from_row(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<Self> {
+        Ok(Self {
+            anime_id: row.get(offset)?,
+            release_group: row.get(offset + 1)?,
+            resolution: row.get(offset + 2)?,
+            source: row.get(offset + 3)?,
+            lock_group: row.get(offset + 4)?,
+        })
+    }
+
+    /// Spells `source` the way release titles are parsed into, failing if it is none of
+    /// [`SOURCES`]. An empty source is no preference.
+    pub fn normalize_source(&mut self) -> KisaraResult<()> {
+        let Some(source) = self.source.take() else {
+            return Ok(());
+        };
+        let source = source.trim().to_uppercase();
+        if source.is_empty() {
+            return Ok(());
+        }
+        if !SOURCES.contains(&source.as_str()) {
+            return Err(KisaraError::UnknownReleaseSource(source));
+        }
+        self.source = Some(source);
+        Ok(())
+    }
+
+    /// The group releases are restricted to, if the group is locked.
+    pub fn locked_group(&self) -> Option<&str> {
+        self.release_group.as_deref().filter(|_| self.lock_group)
+    }
+
+    pub fn matches_group(&self, torrent: &TorrentInfo) -> bool {
+        self.release_group.as_deref().is_none_or(|preferred| {
+            torrent
+                .group()
+                .is_some_and(|g| g.eq_ignore_ascii_case(preferred))
+        })
+    }
+
+    pub fn matches_resolution(&self, torrent: &TorrentInfo) -> bool {
+        self.resolution
+            .is_none_or(|resolution| torrent.release.resolution == Some(resolution))
+    }
+
+    pub fn matches_source(&self, torrent: &TorrentInfo) -> bool {
+        self.source.as_deref().is_some_and(|preferred| {
+            torrent
+                .release
+                .source
+                .as_deref()
+                .is_some_and(|source| source.eq_ignore_ascii_case(preferred))
+        })
+    }
+}
@@ -0,0 +1,66 @@
+use std::{collections::HashMap, path::Path};
+
+use serde::Serialize;
+
+use crate::utils::{release_name::ReleaseInfo, video::is_video_file};
+
+use super::episode::Episode;
+
+/// A file of a batch torrent holding a single episode.
+#[derive(Serialize, Debug, Clone)]
+pub struct EpisodeFile {
+    pub ep_id: i32,
+    pub torrent_id: String,
+    /// Index of the file in the torrent.
+    pub file_index: usize,
+}
+
+impl EpisodeFile {
+    pub fn from_row(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<Self> {
+        Ok(Self {
+            ep_id: row.get(offset)?,
+            torrent_id: row.get(offset + 1)?,
+            file_index: row.get(offset + 2)?,
+        })
+    }
+
+    /// Matches the video files of a torrent to episodes by the episode number in their
+    /// names. Files of several episodes or of no known episode are left out, and when
+    /// several files claim one episode the first is kept.
+    pub fn match_files(
+        torrent_id: &str,
+        files: &[(usize, String)],
+        episodes: &[Episode],
+    ) -> Vec<Self> {
+        let episodes = episodes
+            .iter()
+            .map(|ep| (ep.ep.unwrap_or(ep.sort), ep.id))
+            .collect::<HashMap<_, _>>();
+        let mut matched = HashMap::new();
+        for file in files {
+            let name = Path::new(&file.1);
+            if !is_video_file(name) {
+                continue;
+            }
+            let Some(range) = name
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| ReleaseInfo::parse(name).episodes)
+                .filter(|range| range.start == range.end)
+            else {
+                continue;
+            };
+            if let Some(ep_id) = episodes.get(&range.start) {
+                matched.entry(*ep_id).or_insert(file.0);
+            }
+        }
+        matched
+            .into_iter()
+            .map(|(ep_id, file_index)| Self {
+                ep_id,
+                torrent_id: torrent_id.to_owned(),
+                file_index,
+            })
+            .collect()
+    }
+}
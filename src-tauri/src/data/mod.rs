@@ -1,3 +1,10 @@
 pub mod anime;
+pub mod anime_preference;
 pub mod episode;
+pub mod episode_file;
 pub mod search;
+pub mod seeding;
+pub mod subscription;
+pub mod torrent_control;
+pub mod torrent_record;
+pub mod torrent_upgrade;
@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// When a finished torrent stops seeding, whichever limit is reached first. Without limits
+/// it seeds until it is paused or removed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct SeedingPolicy {
+    /// Uploaded bytes over the size of the torrent.
+    pub max_ratio: Option<f64>,
+    pub max_hours: Option<u32>,
+}
+
+impl SeedingPolicy {
+    pub fn from_row(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<Self> {
+        Ok(Self {
+            max_ratio: row.get(offset)?,
+            max_hours: row.get(offset + 1)?,
+        })
+    }
+
+    pub fn is_reached(
+        &self,
+        seeding: &TorrentSeeding,
+        total_bytes: u64,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let ratio_reached = self
+            .max_ratio
+            .is_some_and(|max| seeding.ratio(total_bytes) >= max);
+        let time_reached = self
+            .max_hours
+            .is_some_and(|max| now - seeding.since >= chrono::Duration::hours(max.into()));
+        ratio_reached || time_reached
+    }
+}
+
+/// Seeding progress of a finished torrent, kept across restarts since librqbit only counts
+/// what was uploaded since the torrent last started.
+#[derive(Serialize, Debug, Clone)]
+pub struct TorrentSeeding {
+    pub info_hash: String,
+    /// When the torrent was first seen finished.
+    pub since: DateTime<Utc>,
+    pub uploaded_bytes: u64,
+    /// Stopped by the seeding policy. The policy is not applied again if the user resumes it.
+    pub stopped: bool,
+}
+
+impl TorrentSeeding {
+    pub const fn new(info_hash: String, since: DateTime<Utc>) -> Self {
+        Self {
+            info_hash,
+            since,
+            uploaded_bytes: 0,
+            stopped: false,
+        }
+    }
+
+    pub fn from_row(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<Self> {
+        Ok(Self {
+            info_hash: row.get(offset)?,
+            since: row.get(offset + 1)?,
+            uploaded_bytes: row.get(offset + 2)?,
+            stopped: row.get(offset + 3)?,
+        })
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    pub fn ratio(&self, total_bytes: u64) -> f64 {
+        if total_bytes == 0 {
+            return 0.0;
+        }
+        self.uploaded_bytes as f64 / total_bytes as f64
+    }
+}
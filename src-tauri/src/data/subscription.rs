@@ -0,0 +1,56 @@
+use chrono::{DateTime, Duration, FixedOffset, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::torrent_adapters::TorrentInfo;
+
+use super::{anime_preference::AnimePreference, episode::Episode};
+
+const JST_OFFSET_SECS: i32 = 9 * 3600;
+
+/// Auto-download schedule of an anime. Releases have to match the group and resolution of
+/// its [`AnimePreference`], if any.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Subscription {
+    pub anime_id: i32,
+    /// Hours after the start of the air date before searching, giving groups time to release.
+    pub delay_hours: u32,
+    /// Hours to keep searching for an episode before giving up on it.
+    pub retry_hours: u32,
+}
+
+impl Subscription {
+    pub fn from_row(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<Self> {
+        Ok(Self {
+            anime_id: row.get(offset)?,
+            delay_hours: row.get(offset + 1)?,
+            retry_hours: row.get(offset + 2)?,
+        })
+    }
+
+    /// Start of the episode's air date, Bangumi air dates being Japanese calendar days.
+    fn aired_at(ep: &Episode) -> Option<DateTime<FixedOffset>> {
+        let offset = FixedOffset::east_opt(JST_OFFSET_SECS)?;
+        ep.air_date?
+            .and_time(NaiveTime::MIN)
+            .and_local_timezone(offset)
+            .single()
+    }
+
+    /// Whether `now` falls in the window in which the episode should be searched for.
+    pub fn is_due(&self, ep: &Episode, now: DateTime<Utc>) -> bool {
+        Self::aired_at(ep).is_some_and(|aired| {
+            let start = aired + Duration::hours(self.delay_hours.into());
+            let end = start + Duration::hours(self.retry_hours.into());
+            start <= now && now < end
+        })
+    }
+
+    /// Whether the torrent is a single-episode release of `ep` matching the preference.
+    pub fn accepts(torrent: &TorrentInfo, ep: i32, preference: Option<&AnimePreference>) -> bool {
+        torrent
+            .release
+            .episodes
+            .is_some_and(|e| e.start == ep && e.end == ep)
+            && preference.is_none_or(|p| p.matches_group(torrent) && p.matches_resolution(torrent))
+    }
+}
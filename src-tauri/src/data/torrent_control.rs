@@ -0,0 +1,21 @@
+use serde::Serialize;
+
+/// How the download queue treats a torrent. Torrents without one have the defaults.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct TorrentControl {
+    pub info_hash: String,
+    /// Higher priorities download first.
+    pub priority: i32,
+    /// Paused by the user, the queue never resumes it.
+    pub paused: bool,
+}
+
+impl TorrentControl {
+    pub fn from_row(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<Self> {
+        Ok(Self {
+            info_hash: row.get(offset)?,
+            priority: row.get(offset + 1)?,
+            paused: row.get(offset + 2)?,
+        })
+    }
+}
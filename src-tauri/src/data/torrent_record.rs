@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+/// What it takes to add a torrent to the session again, should it go missing.
+#[derive(Serialize, Debug, Clone)]
+pub struct TorrentRecord {
+    /// Hex encoded, also what episodes refer to the torrent by.
+    pub info_hash: String,
+    pub magnet: String,
+    /// The `.torrent` file, so the metadata need not be fetched from peers again.
+    #[serde(skip)]
+    pub torrent_bytes: Option<Vec<u8>>,
+}
+
+impl TorrentRecord {
+    pub fn from_row(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<Self> {
+        Ok(Self {
+            info_hash: row.get(offset)?,
+            magnet: row.get(offset + 1)?,
+            torrent_bytes: row.get(offset + 2)?,
+        })
+    }
+}
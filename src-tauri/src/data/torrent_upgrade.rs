@@ -0,0 +1,21 @@
+use serde::Serialize;
+
+/// A torrent downloading to replace the current torrent of an episode, e.g. a v2 release
+/// or a better encode. The episode keeps playing the old one until this completes.
+#[derive(Serialize, Debug, Clone)]
+pub struct TorrentUpgrade {
+    pub ep_id: i32,
+    pub torrent_id: String,
+    /// Whether to delete the replaced torrent and its files.
+    pub delete_old: bool,
+}
+
+impl TorrentUpgrade {
+    pub fn from_row(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<Self> {
+        Ok(Self {
+            ep_id: row.get(offset)?,
+            torrent_id: row.get(offset + 1)?,
+            delete_old: row.get(offset + 2)?,
+        })
+    }
+}
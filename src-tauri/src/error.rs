@@ -21,9 +21,15 @@ pub enum KisaraError {
     #[error("HTML parse error: {0}")]
     HtmlParseError(String),
 
+    #[error("XML parse error: {0}")]
+    XmlParseError(#[from] quick_xml::DeError),
+
     #[error("No such torrent adapter: {0}")]
     NoSuchTorrentAdapter(String),
 
+    #[error("Another source is already named {0}")]
+    DuplicateSourceName(String),
+
     #[error("No such anime with ID: {0}")]
     NoSuchAnime(i32),
 
@@ -54,12 +60,24 @@ pub enum KisaraError {
     #[error("Command failed: {0}")]
     CommandFailed(String),
 
+    #[error("Unknown release source: {0}")]
+    UnknownReleaseSource(String),
+
     #[error("No video found in torrent: {0}")]
     NoVideoFoundInTorrent(String),
 
+    #[error("Not enough disk space: {required} bytes needed, {available} bytes available")]
+    InsufficientDiskSpace { required: u64, available: u64 },
+
     #[error("Failed to migrate database: {0}")]
     DbMigrationError(#[from] rusqlite_migration::Error),
 
+    #[error("Downloads are being moved, try again once they are")]
+    Relocating,
+
+    #[error("A file is being streamed, try again once it ends")]
+    Streaming,
+
     #[error("{0}")]
     Any(String),
 }
@@ -42,3 +42,247 @@ impl Event for TorrentInit {
         Ok(())
     }
 }
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SubscriptionDownload {
+    pub anime_id: i32,
+    pub ep_id: i32,
+    pub torrent_id: String,
+    pub name: String,
+}
+
+impl SubscriptionDownload {
+    pub const fn new(anime_id: i32, ep_id: i32, torrent_id: String, name: String) -> Self {
+        Self {
+            anime_id,
+            ep_id,
+            torrent_id,
+            name,
+        }
+    }
+}
+
+impl Event for SubscriptionDownload {
+    fn emit(self, handle: &AppHandle) -> KisaraResult<()> {
+        handle.emit("subscription-download", self)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TorrentReplaced {
+    pub ep_id: i32,
+    pub torrent_id: String,
+    /// The torrent the episode played from before, if any.
+    pub replaced: Option<String>,
+}
+
+impl TorrentReplaced {
+    pub const fn new(ep_id: i32, torrent_id: String, replaced: Option<String>) -> Self {
+        Self {
+            ep_id,
+            torrent_id,
+            replaced,
+        }
+    }
+}
+
+impl Event for TorrentReplaced {
+    fn emit(self, handle: &AppHandle) -> KisaraResult<()> {
+        handle.emit("torrent-replaced", self)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TorrentPaused {
+    pub id: String,
+    /// Paused by the queue to let higher priority torrents download, not by the user.
+    pub queued: bool,
+}
+
+impl TorrentPaused {
+    pub const fn new(id: String, queued: bool) -> Self {
+        Self { id, queued }
+    }
+}
+
+impl Event for TorrentPaused {
+    fn emit(self, handle: &AppHandle) -> KisaraResult<()> {
+        handle.emit("torrent-paused", self)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TorrentResumed {
+    pub id: String,
+}
+
+impl TorrentResumed {
+    pub const fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+impl Event for TorrentResumed {
+    fn emit(self, handle: &AppHandle) -> KisaraResult<()> {
+        handle.emit("torrent-resumed", self)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SeedingStopped {
+    pub id: String,
+}
+
+impl SeedingStopped {
+    pub const fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+impl Event for SeedingStopped {
+    fn emit(self, handle: &AppHandle) -> KisaraResult<()> {
+        handle.emit("seeding-stopped", self)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum TorrentState {
+    Initializing,
+    Live,
+    Paused,
+    Error,
+}
+
+/// Where a torrent is at, as sent in `TorrentProgress`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct TorrentProgressEntry {
+    pub id: String,
+    pub anime_name: Option<String>,
+    pub ep: Option<i32>,
+    pub state: TorrentState,
+    pub error: Option<String>,
+    pub finished: bool,
+    pub progress_bytes: u64,
+    pub uploaded_bytes: u64,
+    pub total_bytes: u64,
+    /// In MiB/s.
+    pub download_speed: f64,
+    /// In MiB/s.
+    pub upload_speed: f64,
+    /// Seconds left at the current download speed.
+    pub eta_secs: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TorrentProgress {
+    /// Only the torrents that changed since the last event.
+    pub torrents: Vec<TorrentProgressEntry>,
+    /// How many torrents are still downloading.
+    pub downloading: u32,
+}
+
+impl TorrentProgress {
+    pub const fn new(torrents: Vec<TorrentProgressEntry>, downloading: u32) -> Self {
+        Self {
+            torrents,
+            downloading,
+        }
+    }
+}
+
+impl Event for TorrentProgress {
+    fn emit(self, handle: &AppHandle) -> KisaraResult<()> {
+        handle.emit("torrent-progress", self)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TorrentError {
+    pub id: String,
+    pub error: String,
+}
+
+impl TorrentError {
+    pub const fn new(id: String, error: String) -> Self {
+        Self { id, error }
+    }
+}
+
+impl Event for TorrentError {
+    fn emit(self, handle: &AppHandle) -> KisaraResult<()> {
+        handle.emit("torrent-error", self)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TorrentRemoved {
+    pub id: String,
+}
+
+impl TorrentRemoved {
+    pub const fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+impl Event for TorrentRemoved {
+    fn emit(self, handle: &AppHandle) -> KisaraResult<()> {
+        handle.emit("torrent-removed", self)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RelocationProgress {
+    pub moved_bytes: u64,
+    pub total_bytes: u64,
+}
+
+impl RelocationProgress {
+    pub const fn new(moved_bytes: u64, total_bytes: u64) -> Self {
+        Self {
+            moved_bytes,
+            total_bytes,
+        }
+    }
+}
+
+impl Event for RelocationProgress {
+    fn emit(self, handle: &AppHandle) -> KisaraResult<()> {
+        handle.emit("relocation-progress", self)?;
+        Ok(())
+    }
+}
+
+/// A torrent was refused or paused because it would fill the download volume.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DiskSpaceLow {
+    pub id: String,
+    pub required_bytes: u64,
+    pub available_bytes: u64,
+}
+
+impl DiskSpaceLow {
+    pub const fn new(id: String, required_bytes: u64, available_bytes: u64) -> Self {
+        Self {
+            id,
+            required_bytes,
+            available_bytes,
+        }
+    }
+}
+
+impl Event for DiskSpaceLow {
+    fn emit(self, handle: &AppHandle) -> KisaraResult<()> {
+        handle.emit("disk-space-low", self)?;
+        Ok(())
+    }
+}
@@ -6,8 +6,11 @@ use tauri::State;
 use crate::{
     data::{
         anime::Anime,
+        anime_preference::AnimePreference,
         episode::Episode,
         search::{AnimeSearchResultItem, Paginated, SortType},
+        seeding::SeedingPolicy,
+        subscription::Subscription,
     },
     error::KisaraResult,
     states::{BgmApiClientState, DatabaseHelperState},
@@ -199,3 +202,80 @@ pub async fn set_anime_keywords(
         .await?;
     Ok(())
 }
+
+#[tauri::command]
+pub async fn get_subscription(
+    db_helper: State<'_, DatabaseHelperState>,
+    anime_id: i32,
+) -> KisaraResult<Option<Subscription>> {
+    db_helper.lock().await.get_subscription(anime_id).await
+}
+
+#[tauri::command]
+pub async fn set_subscription(
+    db_helper: State<'_, DatabaseHelperState>,
+    subscription: Subscription,
+) -> KisaraResult<()> {
+    db_helper
+        .lock()
+        .await
+        .set_subscription(subscription)
+        .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_subscription(
+    db_helper: State<'_, DatabaseHelperState>,
+    anime_id: i32,
+) -> KisaraResult<()> {
+    db_helper.lock().await.remove_subscription(anime_id).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_anime_preference(
+    db_helper: State<'_, DatabaseHelperState>,
+    anime_id: i32,
+) -> KisaraResult<Option<AnimePreference>> {
+    db_helper.lock().await.get_anime_preference(anime_id).await
+}
+
+#[tauri::command]
+pub async fn set_anime_preference(
+    db_helper: State<'_, DatabaseHelperState>,
+    mut preference: AnimePreference,
+) -> KisaraResult<()> {
+    preference.normalize_source()?;
+    db_helper
+        .lock()
+        .await
+        .set_anime_preference(preference)
+        .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_anime_seeding_policy(
+    db_helper: State<'_, DatabaseHelperState>,
+    anime_id: i32,
+) -> KisaraResult<Option<SeedingPolicy>> {
+    db_helper
+        .lock()
+        .await
+        .get_anime_seeding_policy(anime_id)
+        .await
+}
+
+#[tauri::command]
+pub async fn set_anime_seeding_policy(
+    db_helper: State<'_, DatabaseHelperState>,
+    anime_id: i32,
+    policy: Option<SeedingPolicy>,
+) -> KisaraResult<()> {
+    db_helper
+        .lock()
+        .await
+        .set_anime_seeding_policy(anime_id, policy)
+        .await
+}
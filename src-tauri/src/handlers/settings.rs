@@ -1,14 +1,22 @@
-use tauri::{AppHandle, State};
+use std::path::Path;
+
+use tauri::{AppHandle, Manager, State, async_runtime::spawn_blocking};
 use tauri_plugin_dialog::DialogExt;
 
 use crate::{
+    data::seeding::SeedingPolicy,
     error::KisaraResult,
     states::{
         bgm_api::BgmApiClient,
-        config::{KisaraConfig, LogLevelFilter},
-        BgmApiClientState, ConfigState, TorrentAdapterRegistryState,
+        config::{
+            BandwidthLimits, BandwidthRule, KisaraConfig, LogLevelFilter, NyaaConfig,
+            RankingProfile, RetentionPolicy, RssFeedConfig, SearchConfig,
+            TorznabIndexerConfig,
+        },
+        BgmApiClientState, ConfigState, QbitClientState, TorrentAdapterRegistryState,
     },
-    torrent_adapters::TorrentAdapterRegistry,
+    tasks::{self, cleanup::CleanupCandidate},
+    torrent_adapters::{validate_source_names, TorrentAdapterRegistry},
     TracingReloadHandle,
 };
 
@@ -69,11 +77,14 @@ pub async fn set_torrents_proxy(
         config.network_config.torrents_proxy = Some(p);
     }
 
-    let new_registry = TorrentAdapterRegistry::new(if enabled {
-        config.network_config.torrents_proxy.clone()
-    } else {
-        None
-    });
+    let new_registry = TorrentAdapterRegistry::new(
+        if enabled {
+            config.network_config.torrents_proxy.clone()
+        } else {
+            None
+        },
+        config.search_config.clone(),
+    );
     *registry.lock().await = new_registry;
 
     config.network_config.torrents_proxy_enabled = enabled;
@@ -82,26 +93,250 @@ pub async fn set_torrents_proxy(
     Ok(config.clone())
 }
 
+#[tauri::command]
+pub async fn set_torznab_indexers(
+    config: State<'_, ConfigState>,
+    indexers: Vec<TorznabIndexerConfig>,
+    registry: State<'_, TorrentAdapterRegistryState>,
+) -> KisaraResult<KisaraConfig> {
+    let mut config = config.lock().await;
+    let search_config = SearchConfig {
+        torznab_indexers: indexers,
+        ..config.search_config.clone()
+    };
+    validate_source_names(&search_config)?;
+    config.search_config = search_config;
+
+    *registry.lock().await = rebuild_registry(&config);
+
+    config.write_config()?;
+    Ok(config.clone())
+}
+
+#[tauri::command]
+pub async fn set_rss_feeds(
+    config: State<'_, ConfigState>,
+    feeds: Vec<RssFeedConfig>,
+    registry: State<'_, TorrentAdapterRegistryState>,
+) -> KisaraResult<KisaraConfig> {
+    let mut config = config.lock().await;
+    let search_config = SearchConfig {
+        rss_feeds: feeds,
+        ..config.search_config.clone()
+    };
+    validate_source_names(&search_config)?;
+    config.search_config = search_config;
+
+    *registry.lock().await = rebuild_registry(&config);
+
+    config.write_config()?;
+    Ok(config.clone())
+}
+
+#[tauri::command]
+pub async fn set_ranking_profile(
+    config: State<'_, ConfigState>,
+    profile: RankingProfile,
+    registry: State<'_, TorrentAdapterRegistryState>,
+) -> KisaraResult<KisaraConfig> {
+    let mut config = config.lock().await;
+    config.search_config.ranking_profile = profile;
+
+    *registry.lock().await = rebuild_registry(&config);
+
+    config.write_config()?;
+    Ok(config.clone())
+}
+
+#[tauri::command]
+pub async fn set_search_cache_ttl(
+    config: State<'_, ConfigState>,
+    ttl_secs: u64,
+    registry: State<'_, TorrentAdapterRegistryState>,
+) -> KisaraResult<KisaraConfig> {
+    let mut config = config.lock().await;
+    config.search_config.cache_ttl_secs = ttl_secs;
+
+    *registry.lock().await = rebuild_registry(&config);
+
+    config.write_config()?;
+    Ok(config.clone())
+}
+
+#[tauri::command]
+pub async fn set_nyaa_config(
+    config: State<'_, ConfigState>,
+    nyaa: NyaaConfig,
+    registry: State<'_, TorrentAdapterRegistryState>,
+) -> KisaraResult<KisaraConfig> {
+    let mut config = config.lock().await;
+    config.search_config.nyaa = nyaa;
+
+    *registry.lock().await = rebuild_registry(&config);
+
+    config.write_config()?;
+    Ok(config.clone())
+}
+
+fn rebuild_registry(config: &KisaraConfig) -> TorrentAdapterRegistry {
+    TorrentAdapterRegistry::new(
+        if config.network_config.torrents_proxy_enabled {
+            config.network_config.torrents_proxy.clone()
+        } else {
+            None
+        },
+        config.search_config.clone(),
+    )
+}
+
+/// Lets the user pick a new download folder and moves the existing downloads there.
 #[tauri::command]
 pub async fn select_download_path(
     app: AppHandle,
     config: State<'_, ConfigState>,
+    qbit_client: State<'_, QbitClientState>,
 ) -> KisaraResult<KisaraConfig> {
     let folder = app.dialog().file().blocking_pick_folder();
+    let Some(folder) = folder else {
+        return Ok(config.lock().await.clone());
+    };
+
+    let relocation = qbit_client
+        .lock()
+        .await
+        .start_relocation(&folder.into_path().expect("Should work"))
+        .await?;
+    let Some(relocation) = relocation else {
+        return Ok(config.lock().await.clone());
+    };
+
+    // the client stays usable for everything but torrents while the files move
+    let (relocation, moved) = spawn_blocking({
+        let app = app.clone();
+        move || {
+            let moved = relocation.move_downloads(Some(&app));
+            (relocation, moved)
+        }
+    })
+    .await?;
+    // saved before the session opens there, so the downloads are not lost track of if that
+    // fails
+    let moved_ok = moved.is_ok();
+    let saved = match moved {
+        Ok(()) => save_download_path(&app, &config, relocation.new_folder()).await,
+        Err(e) => Err(e),
+    };
+    let folder = qbit_client
+        .lock()
+        .await
+        .finish_relocation(relocation, moved_ok)
+        .await;
+    saved?;
+    folder?;
+
+    Ok(config.lock().await.clone())
+}
 
+async fn save_download_path(
+    app: &AppHandle,
+    config: &ConfigState,
+    folder: &Path,
+) -> KisaraResult<()> {
+    app.asset_protocol_scope().allow_directory(folder, true)?;
     let mut config = config.lock().await;
-    if let Some(f) = folder {
-        f.into_path()
-            .expect("Should work")
-            .to_str()
-            .expect("Should work")
-            .clone_into(&mut config.download_config.download_path);
-        config.write_config()?;
-    }
+    folder
+        .to_str()
+        .expect("Should work")
+        .clone_into(&mut config.download_config.download_path);
+    config.write_config()?;
+    drop(config);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_max_active_downloads(
+    app: AppHandle,
+    config: State<'_, ConfigState>,
+    max_active_downloads: u32,
+) -> KisaraResult<KisaraConfig> {
+    let mut config = config.lock().await;
+    config.download_config.max_active_downloads = max_active_downloads;
+    config.write_config()?;
+    let updated = config.clone();
+    // the queue reads the config, so it has to be unlocked first
+    drop(config);
+
+    tasks::queue::rebalance(&app).await?;
+    Ok(updated)
+}
+
+/// Sets the space kept free on the download volume, which may pause or resume torrents.
+#[tauri::command]
+pub async fn set_disk_reserve(
+    app: AppHandle,
+    config: State<'_, ConfigState>,
+    qbit_client: State<'_, QbitClientState>,
+    reserve_gib: u32,
+) -> KisaraResult<KisaraConfig> {
+    let mut config = config.lock().await;
+    config.download_config.disk_reserve_gib = reserve_gib;
+    config.write_config()?;
+    let updated = config.clone();
+    drop(config);
+
+    qbit_client.lock().await.set_disk_reserve(reserve_gib);
+    tasks::queue::rebalance(&app).await?;
+    Ok(updated)
+}
+
+#[tauri::command]
+pub async fn set_bandwidth_limits(
+    app: AppHandle,
+    config: State<'_, ConfigState>,
+    limits: BandwidthLimits,
+    schedule: Vec<BandwidthRule>,
+) -> KisaraResult<KisaraConfig> {
+    let mut config = config.lock().await;
+    config.download_config.limits = limits;
+    config.download_config.bandwidth_schedule = schedule;
+    config.write_config()?;
+    let updated = config.clone();
+    drop(config);
+
+    tasks::bandwidth::apply_limits(&app).await?;
+    Ok(updated)
+}
+
+#[tauri::command]
+pub async fn set_seeding_policy(
+    config: State<'_, ConfigState>,
+    policy: SeedingPolicy,
+) -> KisaraResult<KisaraConfig> {
+    let mut config = config.lock().await;
+    config.download_config.seeding_policy = policy;
+    config.write_config()?;
+
+    Ok(config.clone())
+}
+
+#[tauri::command]
+pub async fn set_retention_policy(
+    config: State<'_, ConfigState>,
+    policy: RetentionPolicy,
+) -> KisaraResult<KisaraConfig> {
+    let mut config = config.lock().await;
+    config.download_config.retention_policy = policy;
+    config.write_config()?;
 
     Ok(config.clone())
 }
 
+/// What the retention policy would delete now, so it can be checked before enabling it.
+#[tauri::command]
+pub async fn preview_cleanup(app: AppHandle) -> KisaraResult<Vec<CleanupCandidate>> {
+    tasks::cleanup::plan(&app).await
+}
+
 #[tauri::command]
 pub async fn set_log_level(
     config: State<'_, ConfigState>,
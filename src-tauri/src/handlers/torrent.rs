@@ -1,31 +1,80 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use serde::Serialize;
-use tauri::State;
+use tauri::{AppHandle, State};
+use tracing::info;
 
 use crate::{
+    data::{
+        episode_file::EpisodeFile, seeding::TorrentSeeding, torrent_control::TorrentControl,
+        torrent_record::TorrentRecord, torrent_upgrade::TorrentUpgrade,
+    },
     error::KisaraResult,
+    events::{Event, TorrentPaused, TorrentResumed},
     states::{
-        qbit::ManagedTorrentInfo, DatabaseHelperState, QbitClientState, TorrentAdapterRegistryState,
+        DatabaseHelperState, QbitClientState, TorrentAdapterRegistryState,
+        db::DatabaseHelper,
+        qbit::{ManagedTorrentInfo, TorrentFile},
     },
-    torrent_adapters::TorrentInfo,
+    tasks,
+    torrent_adapters::SearchResults,
 };
 
 #[tauri::command]
 pub async fn init_search_torrents(
     ep_id: i32,
+    query: Option<String>,
+    force_refresh: Option<bool>,
     registry: State<'_, TorrentAdapterRegistryState>,
     db_helper: State<'_, DatabaseHelperState>,
-) -> KisaraResult<HashMap<String, Vec<TorrentInfo>>> {
-    let (anime, ep) = db_helper
+) -> KisaraResult<SearchResults> {
+    let db_helper = db_helper.lock().await;
+    let (anime, ep) = db_helper.get_anime_and_ep_with_ep_id(ep_id).await?;
+    let preference = db_helper.get_anime_preference(anime.id).await?;
+    drop(db_helper);
+    let results = registry
         .lock()
         .await
-        .get_anime_and_ep_with_ep_id(ep_id)
-        .await?;
-    let results = registry.lock().await.init_search(&ep, &anime).await?;
+        .init_search(
+            &ep,
+            &anime,
+            preference.as_ref(),
+            query.as_deref(),
+            force_refresh.unwrap_or_default(),
+        )
+        .await;
     Ok(results)
 }
 
+#[tauri::command]
+pub async fn search_torrents_page(
+    ep_id: i32,
+    source: String,
+    page: u32,
+    query: Option<String>,
+    force_refresh: Option<bool>,
+    registry: State<'_, TorrentAdapterRegistryState>,
+    db_helper: State<'_, DatabaseHelperState>,
+) -> KisaraResult<SearchResults> {
+    let db_helper = db_helper.lock().await;
+    let (anime, ep) = db_helper.get_anime_and_ep_with_ep_id(ep_id).await?;
+    let preference = db_helper.get_anime_preference(anime.id).await?;
+    drop(db_helper);
+    registry
+        .lock()
+        .await
+        .search(
+            &source,
+            &ep,
+            &anime,
+            preference.as_ref(),
+            page,
+            query.as_deref(),
+            force_refresh.unwrap_or_default(),
+        )
+        .await
+}
+
 #[tauri::command]
 pub async fn get_downloading_torrents_num(
     qbit_client: State<'_, QbitClientState>,
@@ -40,13 +89,134 @@ pub async fn add_torrent(
     db_helper: State<'_, DatabaseHelperState>,
     magnet: String,
     ep_id: i32,
+    group: Option<String>,
 ) -> KisaraResult<()> {
-    let torrent_id = qbit_client.lock().await.add_torrent(&magnet).await?;
+    let qbit_client = qbit_client.lock().await;
+    let record = qbit_client.add_torrent(&magnet).await?;
+    let files = qbit_client.get_file_names(&record.info_hash)?;
+    drop(qbit_client);
+
+    let db_helper = db_helper.lock().await;
+    link_torrent(&db_helper, record, &files, ep_id, group).await?;
+    drop(db_helper);
+    Ok(())
+}
+
+/// Adds a torrent paused and lists its files once they can be chosen. Passing the choice to
+/// `select_torrent_files` and resuming the torrent starts the download.
+#[tauri::command]
+pub async fn add_torrent_for_selection(
+    app: AppHandle,
+    qbit_client: State<'_, QbitClientState>,
+    db_helper: State<'_, DatabaseHelperState>,
+    magnet: String,
+    ep_id: i32,
+    group: Option<String>,
+) -> KisaraResult<(String, Vec<TorrentFile>)> {
+    let record = qbit_client.lock().await.resolve_torrent(&magnet).await?;
+    let torrent_id = record.info_hash.clone();
+    // paused before the session has it, so the queue cannot start it before the files are
+    // chosen
     db_helper
         .lock()
         .await
-        .set_episode_torrent_id(ep_id, torrent_id)
+        .set_torrent_paused(torrent_id.clone(), true)
         .await?;
+
+    let qbit_client_guard = qbit_client.lock().await;
+    qbit_client_guard.add_torrent_paused(&record).await?;
+    let files = qbit_client_guard.get_file_names(&torrent_id)?;
+    let initialized = qbit_client_guard.wait_until_initialized(&torrent_id)?;
+    drop(qbit_client_guard);
+
+    let db_helper = db_helper.lock().await;
+    link_torrent(&db_helper, record, &files, ep_id, group).await?;
+    drop(db_helper);
+    tasks::queue::rebalance(&app).await?;
+
+    initialized.await?;
+    let files = qbit_client.lock().await.get_torrent_files(&torrent_id)?;
+    Ok((torrent_id, files))
+}
+
+/// Records a newly added torrent and links it to the episode, or to every episode it has
+/// files of.
+async fn link_torrent(
+    db_helper: &DatabaseHelper,
+    record: TorrentRecord,
+    files: &[(usize, String)],
+    ep_id: i32,
+    group: Option<String>,
+) -> KisaraResult<()> {
+    let torrent_id = record.info_hash.clone();
+    db_helper.save_torrent(record).await?;
+    let anime = db_helper.get_anime_with_ep_id(ep_id).await?;
+    let episodes = db_helper.get_episodes(anime.id).await?;
+    let episode_files = EpisodeFile::match_files(&torrent_id, files, &episodes);
+    // a torrent with files of several episodes is a batch and serves all of them
+    if episode_files.len() > 1 {
+        info!(episodes = episode_files.len(), "Adding batch torrent");
+        db_helper.set_episode_files(episode_files).await?;
+    } else {
+        db_helper.set_episode_torrent_id(ep_id, torrent_id).await?;
+    }
+    if let Some(group) = group {
+        db_helper.lock_anime_group(anime.id, group).await?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_torrent_files(
+    qbit_client: State<'_, QbitClientState>,
+    torrent_id: String,
+) -> KisaraResult<Vec<TorrentFile>> {
+    qbit_client.lock().await.get_torrent_files(&torrent_id)
+}
+
+/// Downloads only the given files of a torrent. Episodes of a batch whose files are left
+/// out no longer play from it.
+#[tauri::command]
+pub async fn select_torrent_files(
+    qbit_client: State<'_, QbitClientState>,
+    db_helper: State<'_, DatabaseHelperState>,
+    torrent_id: String,
+    files: HashSet<usize>,
+) -> KisaraResult<()> {
+    qbit_client
+        .lock()
+        .await
+        .select_files(&torrent_id, &files)
+        .await?;
+    db_helper
+        .lock()
+        .await
+        .unlink_unselected_files(torrent_id, files)
+        .await
+}
+
+/// Downloads a torrent to replace the episode's current one, which stays playable until
+/// the new one completes.
+#[tauri::command]
+pub async fn replace_torrent(
+    app: AppHandle,
+    qbit_client: State<'_, QbitClientState>,
+    db_helper: State<'_, DatabaseHelperState>,
+    magnet: String,
+    ep_id: i32,
+    delete_old: bool,
+) -> KisaraResult<()> {
+    let record = qbit_client.lock().await.add_torrent(&magnet).await?;
+    let upgrade = TorrentUpgrade {
+        ep_id,
+        torrent_id: record.info_hash.clone(),
+        delete_old,
+    };
+    let db_helper = db_helper.lock().await;
+    db_helper.save_torrent(record).await?;
+    db_helper.set_torrent_upgrade(upgrade.clone()).await?;
+    drop(db_helper);
+    tasks::upgrade::watch(&app, upgrade);
     Ok(())
 }
 
@@ -56,6 +226,11 @@ pub struct TorrentStat {
     pub ep: i32,
     pub info: ManagedTorrentInfo,
     pub torrent_id: String,
+    pub control: TorrentControl,
+    /// Only for finished torrents.
+    pub seeding: Option<TorrentSeeding>,
+    /// Whether this is a better release downloading to replace the episode's torrent.
+    pub upgrade: bool,
 }
 
 #[tauri::command]
@@ -64,15 +239,37 @@ pub async fn get_torrent_stats(
     db_helper: State<'_, DatabaseHelperState>,
 ) -> KisaraResult<Vec<TorrentStat>> {
     let stats = qbit_client.lock().await.get_torrent_stats();
-    let mut stats = stats.into_iter().collect::<Vec<_>>();
-    stats.sort_by(|a, b| a.0.cmp(&b.0));
 
     let db_helper = db_helper.lock().await;
+    let mut controls = db_helper
+        .get_torrent_controls()
+        .await?
+        .into_iter()
+        .map(|c| (c.info_hash.clone(), c))
+        .collect::<HashMap<_, _>>();
+    let mut seedings = db_helper
+        .get_torrent_seedings()
+        .await?
+        .into_iter()
+        .map(|s| (s.info_hash.clone(), s))
+        .collect::<HashMap<_, _>>();
+    // replacement torrents are not linked to their episode until they finish
+    let upgrades = db_helper
+        .get_torrent_upgrades()
+        .await?
+        .into_iter()
+        .map(|u| (u.torrent_id, u.ep_id))
+        .collect::<HashMap<_, _>>();
     let mut torrent_stats = Vec::new();
     for (id, torrent) in stats {
-        let ep = db_helper.get_ep_with_torrent_id(id.to_string()).await.ok();
-        if let Some(ep) = ep {
-            let anime = db_helper.get_anime_with_ep_id(ep.id).await?;
+        let linked = match upgrades.get(&id) {
+            Some(&ep_id) => Some((db_helper.get_anime_and_ep_with_ep_id(ep_id).await?, true)),
+            None => match db_helper.get_ep_with_torrent_id(id.clone()).await {
+                Ok(ep) => Some(((db_helper.get_anime_with_ep_id(ep.id).await?, ep), false)),
+                Err(_) => None,
+            },
+        };
+        if let Some(((anime, ep), upgrade)) = linked {
             torrent_stats.push(TorrentStat {
                 anime_name: anime.name_cn.clone(),
                 ep: ep.ep.unwrap_or(ep.sort),
@@ -80,7 +277,13 @@ pub async fn get_torrent_stats(
                     name: torrent.name.clone(),
                     stats: torrent.stats,
                 },
-                torrent_id: id.to_string(),
+                control: controls.remove(&id).unwrap_or_else(|| TorrentControl {
+                    info_hash: id.clone(),
+                    ..Default::default()
+                }),
+                seeding: seedings.remove(&id),
+                torrent_id: id,
+                upgrade,
             });
         }
     }
@@ -88,6 +291,69 @@ pub async fn get_torrent_stats(
     Ok(torrent_stats)
 }
 
+/// Pauses a torrent until it is resumed, the queue leaves it alone meanwhile.
+#[tauri::command]
+pub async fn pause_torrent(
+    app: AppHandle,
+    db_helper: State<'_, DatabaseHelperState>,
+    qbit_client: State<'_, QbitClientState>,
+    torrent_id: String,
+) -> KisaraResult<()> {
+    db_helper
+        .lock()
+        .await
+        .set_torrent_paused(torrent_id.clone(), true)
+        .await?;
+    // the queue only handles torrents that are still downloading
+    let qbit_client = qbit_client.lock().await;
+    if qbit_client.is_finished(&torrent_id)? {
+        qbit_client.pause_torrent(&torrent_id).await?;
+        drop(qbit_client);
+        return TorrentPaused::new(torrent_id, false).emit(&app);
+    }
+    drop(qbit_client);
+    tasks::queue::rebalance(&app).await
+}
+
+/// Hands a paused torrent back to the queue, which resumes it when there is room. Finished
+/// torrents go back to seeding right away, even once their seeding policy stopped them.
+#[tauri::command]
+pub async fn resume_torrent(
+    app: AppHandle,
+    db_helper: State<'_, DatabaseHelperState>,
+    qbit_client: State<'_, QbitClientState>,
+    torrent_id: String,
+) -> KisaraResult<()> {
+    db_helper
+        .lock()
+        .await
+        .set_torrent_paused(torrent_id.clone(), false)
+        .await?;
+    let qbit_client = qbit_client.lock().await;
+    if qbit_client.is_finished(&torrent_id)? {
+        qbit_client.resume_torrent(&torrent_id).await?;
+        drop(qbit_client);
+        return TorrentResumed::new(torrent_id).emit(&app);
+    }
+    drop(qbit_client);
+    tasks::queue::rebalance(&app).await
+}
+
+#[tauri::command]
+pub async fn set_torrent_priority(
+    app: AppHandle,
+    db_helper: State<'_, DatabaseHelperState>,
+    torrent_id: String,
+    priority: i32,
+) -> KisaraResult<()> {
+    db_helper
+        .lock()
+        .await
+        .set_torrent_priority(torrent_id, priority)
+        .await?;
+    tasks::queue::rebalance(&app).await
+}
+
 #[tauri::command]
 pub async fn remove_torrent(
     qbit_client: State<'_, QbitClientState>,
@@ -107,13 +373,21 @@ pub async fn torrent_is_present(
     ep_id: i32,
 ) -> KisaraResult<Option<String>> {
     let db_helper = db_helper.lock().await;
-    let torrent_id = db_helper.get_torrent_id_with_ep_id(ep_id).await?;
-    if let Some(torrent_id) = torrent_id {
-        if qbit_client.lock().await.torrent_exists(&torrent_id)? {
-            return Ok(Some(torrent_id));
-        }
-        db_helper.remove_torrent(torrent_id).await?;
+    let Some(torrent_id) = db_helper.get_torrent_id_with_ep_id(ep_id).await? else {
+        return Ok(None);
+    };
+    let qbit_client = qbit_client.lock().await;
+    if qbit_client.torrent_exists(&torrent_id) {
+        return Ok(Some(torrent_id));
+    }
+    // the session lost the torrent, e.g. with its persistence folder, so add it again
+    if let Some(record) = db_helper.get_torrent_record(torrent_id.clone()).await? {
+        info!(torrent_id, "Restoring missing torrent");
+        qbit_client.restore_torrent(&record).await?;
+        return Ok(Some(torrent_id));
     }
+    drop(qbit_client);
+    db_helper.remove_torrent(torrent_id).await?;
     drop(db_helper);
     Ok(None)
 }
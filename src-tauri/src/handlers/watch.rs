@@ -1,10 +1,11 @@
 use serde::Serialize;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
 use crate::{
     data::{anime::Anime, episode::Episode},
     error::KisaraResult,
     states::{ConfigState, DatabaseHelperState, QbitClientState},
+    tasks::stream::StreamServer,
     utils::subtitle::transform_subtitles,
 };
 
@@ -18,31 +19,62 @@ pub struct PlayServeInfo {
 pub struct PlayInfo {
     pub video: String,
     pub subtitles: Vec<String>,
+    /// Where the video is served while it downloads, for the player and external ones.
+    pub stream_url: Option<String>,
     pub ep: Episode,
     pub anime: Anime,
 }
 
 #[tauri::command]
 pub async fn parse_torrent_play_info_v2(
+    app: AppHandle,
     torrent_id: String,
+    ep_id: Option<i32>,
     qbit_client: State<'_, QbitClientState>,
     db_helper: State<'_, DatabaseHelperState>,
     config: State<'_, ConfigState>,
 ) -> KisaraResult<PlayInfo> {
+    let db_helper = db_helper.lock().await;
+    // a batch torrent holds several episodes, so the episode is given explicitly
+    let (anime, episode) = if let Some(ep_id) = ep_id {
+        db_helper.get_anime_and_ep_with_ep_id(ep_id).await?
+    } else {
+        let episode = db_helper.get_ep_with_torrent_id(torrent_id.clone()).await?;
+        (db_helper.get_anime_with_ep_id(episode.id).await?, episode)
+    };
+    let file = db_helper
+        .get_episode_file(episode.id)
+        .await?
+        .filter(|file| file.torrent_id == torrent_id)
+        .map(|file| file.file_index);
+    drop(db_helper);
+
+    let ep = Some(episode.ep.unwrap_or(episode.sort));
+    let qbit_client = qbit_client.lock().await;
+    let stream_server = app.try_state::<StreamServer>();
+    // the files cannot be probed before they are on disk, and neither can subtitles be read
+    let (video, subtitles, stream_url) = match stream_server {
+        Some(server) if !qbit_client.is_finished(&torrent_id)? => {
+            let file = qbit_client.find_video_file(&torrent_id, ep, file)?;
+            let video = qbit_client.get_file_path(&torrent_id, file)?;
+            (video, Vec::new(), Some(server.url(&torrent_id, file)))
+        }
+        _ => {
+            let (video, subtitles) = qbit_client.get_files(&torrent_id, ep, file).await?;
+            (video, subtitles, None)
+        }
+    };
+    drop(qbit_client);
+
     let config = config.lock().await;
     let base_dir = &config.download_config.download_path;
-    let (video, subtitles) = qbit_client.lock().await.get_files(&torrent_id).await?;
     let subtitles = transform_subtitles(base_dir, &video, &subtitles).await?;
-
-    let db_helper = db_helper.lock().await;
-    let episode = db_helper.get_ep_with_torrent_id(torrent_id.clone()).await?;
-    let anime = db_helper.get_anime_with_ep_id(episode.id).await?;
-    drop(db_helper);
     drop(config);
 
     let play_info = PlayInfo {
         video,
         subtitles,
+        stream_url,
         ep: episode,
         anime,
     };
@@ -54,12 +86,13 @@ pub async fn parse_torrent_play_info_v2(
 pub async fn set_progress(
     ep_id: i32,
     progress: u32,
+    duration: Option<u32>,
     db_helper: State<'_, DatabaseHelperState>,
 ) -> KisaraResult<()> {
     db_helper
         .lock()
         .await
-        .update_progress(ep_id, progress)
+        .update_progress(ep_id, progress, duration)
         .await?;
     Ok(())
 }
@@ -1,6 +1,6 @@
 #![feature(error_generic_member_access)]
 
-use error::KisaraResult;
+use error::{KisaraError, KisaraResult};
 use states::{
     BgmApiClientState, ConfigState, QbitClientState, TorrentAdapterRegistryState,
     bgm_api::BgmApiClient,
@@ -15,7 +15,7 @@ use tauri::{
     menu::{Menu, MenuEvent, MenuItem},
     tray::{TrayIcon, TrayIconBuilder, TrayIconEvent},
 };
-use tracing::{info, level_filters::LevelFilter, trace};
+use tracing::{info, level_filters::LevelFilter, trace, warn};
 use tracing_appender::non_blocking::NonBlocking;
 use tracing_subscriber::{
     Registry,
@@ -33,9 +33,13 @@ mod error;
 mod events;
 mod handlers;
 mod states;
+mod tasks;
 mod torrent_adapters;
 mod utils;
 
+/// The tray icon, which shows the download progress on hover.
+const TRAY_ID: &str = "main";
+
 pub type TracingReloadHandle = Option<
     Handle<
         Filtered<
@@ -51,6 +55,7 @@ pub async fn run(reload_handle: TracingReloadHandle) -> KisaraResult<()> {
     let config = load_config()?;
     let db_helper_state = Mutex::new(DatabaseHelper::try_new()?);
     let qbit_client = QbitClient::new(config.download_config.clone()).await?;
+    migrate_torrent_ids(&db_helper_state, &qbit_client).await;
 
     info!("Setting up kisara app with config: {:?}", config);
     let app = setup_app(config, db_helper_state, qbit_client, reload_handle)?;
@@ -60,6 +65,43 @@ pub async fn run(reload_handle: TracingReloadHandle) -> KisaraResult<()> {
     Ok(())
 }
 
+/// Older versions referred to torrents by session id, which does not survive losing the
+/// session. Torrents the session still has are switched over to their info hash, the others
+/// are forgotten, as nothing else was stored to add them again by.
+async fn migrate_torrent_ids(db_helper: &Mutex<DatabaseHelper>, qbit_client: &QbitClient) {
+    let db_helper = db_helper.lock().await;
+    let torrent_ids = match db_helper.get_legacy_torrent_ids().await {
+        Ok(torrent_ids) => torrent_ids,
+        Err(e) => {
+            warn!(error = %e, "Failed to fetch legacy torrent IDs");
+            return;
+        }
+    };
+    for torrent_id in torrent_ids {
+        let migrated = match qbit_client.get_legacy_torrent_record(&torrent_id) {
+            Ok(record) => {
+                db_helper
+                    .migrate_torrent_id(torrent_id.clone(), record)
+                    .await
+            }
+            Err(KisaraError::NoSuchTorrent(_)) => {
+                warn!(
+                    torrent_id,
+                    "Legacy torrent is gone from the session, forgetting it"
+                );
+                db_helper.remove_torrent(torrent_id.clone()).await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = migrated {
+            warn!(error = %e, torrent_id, "Failed to migrate torrent ID");
+        }
+    }
+    drop(db_helper);
+}
+
+// most of the length is the list of command handlers
+#[allow(clippy::too_many_lines)]
 fn setup_app(
     config: KisaraConfig,
     db_helper_state: Mutex<DatabaseHelper>,
@@ -87,10 +129,25 @@ fn setup_app(
             handlers::get_dashboard_summary,
             handlers::get_air_calendar,
             handlers::set_anime_keywords,
+            handlers::get_subscription,
+            handlers::set_subscription,
+            handlers::remove_subscription,
+            handlers::get_anime_preference,
+            handlers::set_anime_preference,
+            handlers::get_anime_seeding_policy,
+            handlers::set_anime_seeding_policy,
             // torrent handlers
             handlers::init_search_torrents,
+            handlers::search_torrents_page,
             handlers::get_downloading_torrents_num,
             handlers::add_torrent,
+            handlers::add_torrent_for_selection,
+            handlers::get_torrent_files,
+            handlers::select_torrent_files,
+            handlers::replace_torrent,
+            handlers::pause_torrent,
+            handlers::resume_torrent,
+            handlers::set_torrent_priority,
             handlers::get_torrent_stats,
             handlers::remove_torrent,
             handlers::torrent_is_present,
@@ -113,6 +170,17 @@ fn setup_app(
             handlers::set_torrents_proxy,
             handlers::select_download_path,
             handlers::set_log_level,
+            handlers::set_torznab_indexers,
+            handlers::set_rss_feeds,
+            handlers::set_ranking_profile,
+            handlers::set_search_cache_ttl,
+            handlers::set_nyaa_config,
+            handlers::set_max_active_downloads,
+            handlers::set_disk_reserve,
+            handlers::set_bandwidth_limits,
+            handlers::set_seeding_policy,
+            handlers::set_retention_policy,
+            handlers::preview_cleanup,
         ])
         .setup(move |app| {
             app.manage(db_helper_state);
@@ -130,6 +198,7 @@ fn setup_app(
                 } else {
                     None
                 },
+                config.search_config.clone(),
             );
             app.manage(TorrentAdapterRegistryState::new(torrent_adapter_registry));
 
@@ -149,11 +218,13 @@ fn setup_app(
             app.manage(handle);
             app.manage(ConfigState::new(config));
 
+            tasks::start(app.handle());
+
             let menu_item_quit = MenuItem::with_id(app, "exit", "Exit", true, None::<&str>)?;
             let menu_item_show = MenuItem::with_id(app, "show", "Show/Hide", true, None::<&str>)?;
             let menu = Menu::with_items(app, &[&menu_item_show, &menu_item_quit])?;
 
-            let _tray = TrayIconBuilder::new()
+            let _tray = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(
                     app.default_window_icon()
                         .expect("This has been set")
@@ -1,14 +1,32 @@
 use std::path::Path;
 
+use chrono::{Datelike, NaiveDateTime, NaiveTime};
 use langtag::LangTagBuf;
 use serde::{Deserialize, Serialize};
 use tracing::level_filters::LevelFilter;
 
-use crate::error::KisaraResult;
+use crate::{data::seeding::SeedingPolicy, error::KisaraResult};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct DownloadConfig {
     pub download_path: String,
+    /// How many torrents may download at once, the rest wait paused. 0 for no limit.
+    #[serde(default)]
+    pub max_active_downloads: u32,
+    #[serde(default)]
+    pub limits: BandwidthLimits,
+    /// Rules that override `limits` at certain times, the first matching one applies.
+    #[serde(default)]
+    pub bandwidth_schedule: Vec<BandwidthRule>,
+    /// Applies to anime without a policy of their own.
+    #[serde(default)]
+    pub seeding_policy: SeedingPolicy,
+    #[serde(default)]
+    pub retention_policy: RetentionPolicy,
+    /// Space left free on the download volume, torrents that would eat into it are refused
+    /// or paused.
+    #[serde(default = "DownloadConfig::default_disk_reserve_gib")]
+    pub disk_reserve_gib: u32,
 }
 
 impl Default for DownloadConfig {
@@ -18,11 +36,29 @@ impl Default for DownloadConfig {
         let canonical_path = Self::canonicalize_path(download_path);
         Self {
             download_path: Self::strip_unc_prefix(&canonical_path),
+            max_active_downloads: 0,
+            limits: BandwidthLimits::default(),
+            bandwidth_schedule: Vec::new(),
+            seeding_policy: SeedingPolicy::default(),
+            retention_policy: RetentionPolicy::default(),
+            disk_reserve_gib: Self::default_disk_reserve_gib(),
         }
     }
 }
 
 impl DownloadConfig {
+    /// The limits that apply at the given local time.
+    pub fn limits_at(&self, now: NaiveDateTime) -> BandwidthLimits {
+        self.bandwidth_schedule
+            .iter()
+            .find(|rule| rule.applies_at(now))
+            .map_or(self.limits, |rule| rule.limits)
+    }
+
+    const fn default_disk_reserve_gib() -> u32 {
+        5
+    }
+
     const fn default_download_path() -> &'static str {
         #[cfg(debug_assertions)]
         {
@@ -53,6 +89,63 @@ impl DownloadConfig {
     }
 }
 
+/// Transfer rates in KiB/s, `None` for unlimited.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct BandwidthLimits {
+    pub upload_kib: Option<u32>,
+    pub download_kib: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BandwidthRule {
+    /// Days of the week, 0 being Monday.
+    pub days: Vec<u32>,
+    pub start: NaiveTime,
+    /// May be before `start` for rules that run past midnight, the part after midnight
+    /// still belongs to the day the rule started on.
+    pub end: NaiveTime,
+    pub limits: BandwidthLimits,
+}
+
+impl BandwidthRule {
+    fn applies_at(&self, now: NaiveDateTime) -> bool {
+        let day = now.weekday().num_days_from_monday();
+        let time = now.time();
+        if self.start <= self.end {
+            self.days.contains(&day) && self.start <= time && time < self.end
+        } else {
+            let previous_day = (day + 6) % 7;
+            (self.days.contains(&day) && self.start <= time)
+                || (self.days.contains(&previous_day) && time < self.end)
+        }
+    }
+}
+
+/// Deletes the torrents of watched episodes. A torrent holding several episodes goes once
+/// all of them qualify.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct RetentionPolicy {
+    pub enabled: bool,
+    /// How much of an episode has to be played for it to count as watched.
+    pub watched_percent: u32,
+    /// How long after last watching an episode its files are kept.
+    pub grace_hours: u32,
+    /// Over this size of downloads, watched episodes go without waiting for the grace
+    /// period, the ones watched longest ago first.
+    pub quota_gib: Option<u32>,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            watched_percent: 90,
+            grace_hours: 72,
+            quota_gib: None,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Default, Debug)]
 pub struct NetworkConfig {
     pub bgm_proxy: Option<String>,
@@ -61,6 +154,170 @@ pub struct NetworkConfig {
     pub torrents_proxy_enabled: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TorznabSearchMode {
+    /// `t=search` with the episode number appended to the query.
+    #[default]
+    Search,
+    /// `t=tvsearch` with the episode number passed as `ep`.
+    TvSearch,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TorznabIndexerConfig {
+    pub name: String,
+    /// API endpoint, e.g. `http://localhost:9117/api/v2.0/indexers/nyaasi/results/torznab`.
+    pub url: String,
+    pub api_key: String,
+    #[serde(default)]
+    pub categories: Vec<u32>,
+    #[serde(default)]
+    pub search_mode: TorznabSearchMode,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RssFeedConfig {
+    pub name: String,
+    /// RSS 2.0 or Atom feed whose entries link to a magnet or a torrent file.
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NyaaCategory {
+    #[default]
+    AllAnime,
+    EnglishTranslated,
+    NonEnglishTranslated,
+    Raw,
+}
+
+impl NyaaCategory {
+    /// Value of nyaa's `c` query parameter.
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::AllAnime => "1_0",
+            Self::EnglishTranslated => "1_2",
+            Self::NonEnglishTranslated => "1_3",
+            Self::Raw => "1_4",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NyaaFilter {
+    NoFilter,
+    NoRemakes,
+    #[default]
+    TrustedOnly,
+}
+
+impl NyaaFilter {
+    /// Value of nyaa's `f` query parameter.
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::NoFilter => "0",
+            Self::NoRemakes => "1",
+            Self::TrustedOnly => "2",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NyaaConfig {
+    /// nyaa.si or a mirror running the same software, e.g. `https://nyaa.land`.
+    #[serde(default = "NyaaConfig::default_base_url")]
+    pub base_url: String,
+    #[serde(default)]
+    pub category: NyaaCategory,
+    #[serde(default)]
+    pub filter: NyaaFilter,
+}
+
+impl Default for NyaaConfig {
+    fn default() -> Self {
+        Self {
+            base_url: Self::default_base_url(),
+            category: NyaaCategory::default(),
+            filter: NyaaFilter::default(),
+        }
+    }
+}
+
+impl NyaaConfig {
+    fn default_base_url() -> String {
+        "https://nyaa.si".to_owned()
+    }
+}
+
+/// How search results from all sources are filtered and ordered.
+///
+/// Group, word and codec matches are case-insensitive. Empty lists and `None` bounds
+/// disable the corresponding rule.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RankingProfile {
+    /// Groups in order of preference.
+    #[serde(default)]
+    pub preferred_groups: Vec<String>,
+    /// Words that must all appear in the release name.
+    #[serde(default)]
+    pub required_words: Vec<String>,
+    /// Words that exclude a release when any of them appears in its name.
+    #[serde(default)]
+    pub forbidden_words: Vec<String>,
+    /// Resolutions (vertical pixels) in order of preference, e.g. `[1080, 720]`.
+    #[serde(default)]
+    pub preferred_resolutions: Vec<u32>,
+    /// Video codecs in order of preference, e.g. `["AVC", "AV1"]`.
+    #[serde(default)]
+    pub preferred_codecs: Vec<String>,
+    /// Video codecs that exclude a release, e.g. `["HEVC"]`.
+    #[serde(default)]
+    pub excluded_codecs: Vec<String>,
+    #[serde(default)]
+    pub min_size_mib: Option<u64>,
+    #[serde(default)]
+    pub max_size_mib: Option<u64>,
+    /// Only applied to sources that report peer counts.
+    #[serde(default)]
+    pub min_seeders: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SearchConfig {
+    #[serde(default)]
+    pub torznab_indexers: Vec<TorznabIndexerConfig>,
+    #[serde(default)]
+    pub rss_feeds: Vec<RssFeedConfig>,
+    #[serde(default)]
+    pub ranking_profile: RankingProfile,
+    /// How long search results are reused before sources are queried again, 0 disables it.
+    #[serde(default = "SearchConfig::default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+    #[serde(default)]
+    pub nyaa: NyaaConfig,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            torznab_indexers: Vec::new(),
+            rss_feeds: Vec::new(),
+            ranking_profile: RankingProfile::default(),
+            cache_ttl_secs: Self::default_cache_ttl_secs(),
+            nyaa: NyaaConfig::default(),
+        }
+    }
+}
+
+impl SearchConfig {
+    const fn default_cache_ttl_secs() -> u64 {
+        30 * 60
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum LogLevelFilter {
@@ -95,6 +352,8 @@ pub struct KisaraConfig {
     pub network_config: NetworkConfig,
     pub locale: String,
     pub debug_config: DebugConfig,
+    #[serde(default)]
+    pub search_config: SearchConfig,
 }
 
 fn system_locale() -> String {
@@ -112,6 +371,7 @@ impl Default for KisaraConfig {
             network_config: NetworkConfig::default(),
             locale: system_locale(),
             debug_config: DebugConfig::default(),
+            search_config: SearchConfig::default(),
         }
     }
 }
@@ -1,4 +1,11 @@
-use std::{collections::BTreeMap, fmt::Debug};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Debug,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
 
 use chrono::NaiveDate;
 use itertools::Itertools;
@@ -10,13 +17,25 @@ use tauri::async_runtime::spawn_blocking;
 use tracing::{info, instrument};
 
 use crate::{
-    data::{anime::Anime, episode::Episode},
+    data::{
+        anime::Anime,
+        anime_preference::AnimePreference,
+        episode::Episode,
+        episode_file::EpisodeFile,
+        seeding::{SeedingPolicy, TorrentSeeding},
+        subscription::Subscription,
+        torrent_control::TorrentControl,
+        torrent_record::TorrentRecord,
+        torrent_upgrade::TorrentUpgrade,
+    },
     error::{KisaraError, KisaraResult},
     utils::season::Season,
 };
 
 pub struct DatabaseHelper {
     conn_pool: Pool<SqliteConnectionManager>,
+    /// Bumped whenever episodes are linked to or unlinked from torrents.
+    torrent_links: Arc<AtomicU64>,
 }
 
 #[cfg(debug_assertions)]
@@ -27,6 +46,14 @@ const DB_PATH: &str = "db.sqlite"; // to include this in the release build
 const MIGRATION_SLICE: &[M<'_>] = &[
     M::up(include_str!("sql/create_table.sql")),
     M::up(include_str!("sql/add_anime_keywords.sql")),
+    M::up(include_str!("sql/add_subscription.sql")),
+    M::up(include_str!("sql/add_anime_preference.sql")),
+    M::up(include_str!("sql/add_episode_file.sql")),
+    M::up(include_str!("sql/add_torrent_upgrade.sql")),
+    M::up(include_str!("sql/add_torrent.sql")),
+    M::up(include_str!("sql/add_torrent_control.sql")),
+    M::up(include_str!("sql/add_seeding.sql")),
+    M::up(include_str!("sql/add_episode_duration.sql")),
 ];
 
 const MIGRATIONS: Migrations<'_> = Migrations::from_slice(MIGRATION_SLICE);
@@ -35,7 +62,10 @@ impl DatabaseHelper {
     pub fn try_new() -> KisaraResult<Self> {
         let manager = SqliteConnectionManager::file(DB_PATH);
         let pool = Pool::builder().max_size(5).build(manager)?;
-        let db_helper = Self { conn_pool: pool };
+        let db_helper = Self {
+            conn_pool: pool,
+            torrent_links: Arc::new(AtomicU64::new(0)),
+        };
         db_helper.init_tables()?;
         Ok(db_helper)
     }
@@ -46,6 +76,15 @@ impl DatabaseHelper {
         Ok(())
     }
 
+    /// Counts changes to which episodes torrents are linked to, for whoever caches the links.
+    pub fn torrent_links(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.torrent_links)
+    }
+
+    fn links_changed(&self) {
+        self.torrent_links.fetch_add(1, Ordering::Relaxed);
+    }
+
     #[instrument(level = "info", skip(self))]
     pub async fn get_animes_between_dates(
         &self,
@@ -188,9 +227,12 @@ impl DatabaseHelper {
         let query = "UPDATE episode SET torrent_id = ?1 WHERE id = ?2";
         spawn_blocking(move || {
             conn.execute(query, params![torrent_id, ep_id])?;
+            // a file of a batch the episode was mapped to no longer applies
+            conn.execute("DELETE FROM episode_file WHERE ep_id = ?1", params![ep_id])?;
             KisaraResult::Ok(())
         })
         .await??;
+        self.links_changed();
         info!("Set torrent ID for episode successfully");
         Ok(())
     }
@@ -199,7 +241,8 @@ impl DatabaseHelper {
     pub async fn get_ep_with_torrent_id(&self, torrent_id: String) -> KisaraResult<Episode> {
         info!("Fetching episode with torrent ID");
         let conn = self.conn_pool.get()?;
-        let query = "SELECT * FROM episode WHERE torrent_id = ?1";
+        // batch torrents hold several episodes, the first one stands for the torrent
+        let query = "SELECT * FROM episode WHERE torrent_id = ?1 ORDER BY sort";
         let episode = spawn_blocking(move || {
             let mut stmt = conn.prepare(query)?;
             let result = stmt
@@ -247,8 +290,15 @@ impl DatabaseHelper {
         Ok(anime)
     }
 
+    /// Also records the length of the episode's video when the player knows it, which tells
+    /// whether the episode was watched.
     #[instrument(level = "info", skip(self))]
-    pub async fn update_progress(&self, ep_id: i32, progress: u32) -> KisaraResult<()> {
+    pub async fn update_progress(
+        &self,
+        ep_id: i32,
+        progress: u32,
+        duration: Option<u32>,
+    ) -> KisaraResult<()> {
         info!("Updating progress for episode");
         let conn = self.conn_pool.get()?;
         // set progress and update last_watch_time
@@ -256,6 +306,12 @@ impl DatabaseHelper {
         let now = chrono::Local::now().naive_local();
         spawn_blocking(move || {
             conn.execute(query, params![progress, now, ep_id])?;
+            if let Some(duration) = duration {
+                conn.execute(
+                    "INSERT OR REPLACE INTO episode_duration (ep_id, duration) VALUES (?1, ?2)",
+                    params![ep_id, duration],
+                )?;
+            }
             KisaraResult::Ok(())
         })
         .await??;
@@ -289,9 +345,30 @@ impl DatabaseHelper {
         let query = "UPDATE episode SET torrent_id = NULL WHERE torrent_id = ?1";
         spawn_blocking(move || {
             conn.execute(query, params![torrent_id])?;
+            conn.execute(
+                "DELETE FROM episode_file WHERE torrent_id = ?1",
+                params![torrent_id],
+            )?;
+            conn.execute(
+                "DELETE FROM torrent_upgrade WHERE torrent_id = ?1",
+                params![torrent_id],
+            )?;
+            conn.execute(
+                "DELETE FROM torrent WHERE info_hash = ?1",
+                params![torrent_id],
+            )?;
+            conn.execute(
+                "DELETE FROM torrent_control WHERE info_hash = ?1",
+                params![torrent_id],
+            )?;
+            conn.execute(
+                "DELETE FROM torrent_seeding WHERE info_hash = ?1",
+                params![torrent_id],
+            )?;
             KisaraResult::Ok(())
         })
         .await??;
+        self.links_changed();
         info!("Removed torrent successfully");
         Ok(())
     }
@@ -509,4 +586,542 @@ impl DatabaseHelper {
         info!(?anime, "Fetched anime by ID");
         Ok(anime)
     }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn get_subscription(&self, anime_id: i32) -> KisaraResult<Option<Subscription>> {
+        info!("Fetching subscription");
+        let conn = self.conn_pool.get()?;
+        let query = "SELECT * FROM subscription WHERE anime_id = ?1";
+        let subscription = spawn_blocking(move || {
+            let mut stmt = conn.prepare(query)?;
+            let result = stmt
+                .query_map(params![anime_id], |row| Subscription::from_row(row, 0))?
+                .next()
+                .transpose()?;
+            KisaraResult::Ok(result)
+        })
+        .await??;
+        info!(?subscription, "Fetched subscription");
+        Ok(subscription)
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn set_subscription(&self, subscription: Subscription) -> KisaraResult<()> {
+        info!("Setting subscription");
+        let conn = self.conn_pool.get()?;
+        let query = "INSERT OR REPLACE INTO subscription (anime_id, delay_hours, retry_hours) VALUES (?1, ?2, ?3)";
+        spawn_blocking(move || {
+            conn.execute(
+                query,
+                params![
+                    subscription.anime_id,
+                    subscription.delay_hours,
+                    subscription.retry_hours
+                ],
+            )?;
+            KisaraResult::Ok(())
+        })
+        .await??;
+        info!("Set subscription successfully");
+        Ok(())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn remove_subscription(&self, anime_id: i32) -> KisaraResult<()> {
+        info!("Removing subscription");
+        let conn = self.conn_pool.get()?;
+        let query = "DELETE FROM subscription WHERE anime_id = ?1";
+        spawn_blocking(move || {
+            conn.execute(query, params![anime_id])?;
+            KisaraResult::Ok(())
+        })
+        .await??;
+        info!("Removed subscription successfully");
+        Ok(())
+    }
+
+    /// Episodes of subscribed animes that have an air date but no torrent yet.
+    #[instrument(level = "info", skip(self))]
+    pub async fn get_subscribed_episodes_without_torrent(
+        &self,
+    ) -> KisaraResult<Vec<(Anime, Episode, Subscription)>> {
+        info!("Fetching subscribed episodes without torrent");
+        let conn = self.conn_pool.get()?;
+        let query = "SELECT a.*, e.*, s.* FROM subscription s JOIN anime a ON a.id = s.anime_id JOIN episode e ON e.anime_id = a.id WHERE e.torrent_id IS NULL AND e.air_date IS NOT NULL";
+        let episodes = spawn_blocking(move || {
+            let mut stmt = conn.prepare(query)?;
+            let result = stmt
+                .query_map(params![], |row| {
+                    let anime = Anime::from_row(row, 0)?;
+                    let episode = Episode::from_row(row, 7)?;
+                    let subscription = Subscription::from_row(row, 17)?;
+                    Ok((anime, episode, subscription))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            KisaraResult::Ok(result)
+        })
+        .await??;
+        info!(
+            count = episodes.len(),
+            "Fetched subscribed episodes without torrent"
+        );
+        Ok(episodes)
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn get_anime_preference(
+        &self,
+        anime_id: i32,
+    ) -> KisaraResult<Option<AnimePreference>> {
+        info!("Fetching anime preference");
+        let conn = self.conn_pool.get()?;
+        let query = "SELECT * FROM anime_preference WHERE anime_id = ?1";
+        let preference = spawn_blocking(move || {
+            let mut stmt = conn.prepare(query)?;
+            let result = stmt
+                .query_map(params![anime_id], |row| AnimePreference::from_row(row, 0))?
+                .next()
+                .transpose()?;
+            KisaraResult::Ok(result)
+        })
+        .await??;
+        info!(?preference, "Fetched anime preference");
+        Ok(preference)
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn set_anime_preference(&self, preference: AnimePreference) -> KisaraResult<()> {
+        info!("Setting anime preference");
+        let conn = self.conn_pool.get()?;
+        let query = "INSERT OR REPLACE INTO anime_preference (anime_id, release_group, resolution, source, lock_group) VALUES (?1, ?2, ?3, ?4, ?5)";
+        spawn_blocking(move || {
+            conn.execute(
+                query,
+                params![
+                    preference.anime_id,
+                    preference.release_group,
+                    preference.resolution,
+                    preference.source,
+                    preference.lock_group
+                ],
+            )?;
+            KisaraResult::Ok(())
+        })
+        .await??;
+        info!("Set anime preference successfully");
+        Ok(())
+    }
+
+    /// Records `group` as the release group of an anime whose group is locked but not chosen
+    /// yet. Does nothing otherwise.
+    #[instrument(level = "info", skip(self))]
+    pub async fn lock_anime_group(&self, anime_id: i32, group: String) -> KisaraResult<()> {
+        info!("Locking anime release group");
+        let conn = self.conn_pool.get()?;
+        let query = "UPDATE anime_preference SET release_group = ?2 WHERE anime_id = ?1 AND lock_group = 1 AND release_group IS NULL";
+        let updated = spawn_blocking(move || {
+            let updated = conn.execute(query, params![anime_id, group])?;
+            KisaraResult::Ok(updated)
+        })
+        .await??;
+        info!(updated, "Locked anime release group");
+        Ok(())
+    }
+
+    /// Links every episode of a batch torrent to the torrent and to its file in it.
+    #[instrument(level = "info", skip(self))]
+    pub async fn set_episode_files(&self, files: Vec<EpisodeFile>) -> KisaraResult<()> {
+        info!("Setting episode files");
+        let conn_pool = self.conn_pool.clone();
+        let result: KisaraResult<()> = spawn_blocking(move || {
+            let mut conn = conn_pool.get()?;
+            let transaction = conn.transaction()?;
+            for file in files {
+                transaction.execute(
+                    "UPDATE episode SET torrent_id = ?1 WHERE id = ?2",
+                    params![file.torrent_id, file.ep_id],
+                )?;
+                transaction.execute(
+                    "INSERT OR REPLACE INTO episode_file (ep_id, torrent_id, file_index) VALUES (?1, ?2, ?3)",
+                    params![file.ep_id, file.torrent_id, file.file_index],
+                )?;
+            }
+            transaction.commit()?;
+            Ok(())
+        })
+        .await?;
+        self.links_changed();
+        info!("Set episode files successfully");
+        result
+    }
+
+    /// Unlinks the episodes of a batch torrent whose files are no longer downloaded.
+    #[instrument(level = "info", skip(self))]
+    pub async fn unlink_unselected_files(
+        &self,
+        torrent_id: String,
+        selected: HashSet<usize>,
+    ) -> KisaraResult<()> {
+        info!("Unlinking unselected episode files");
+        let conn_pool = self.conn_pool.clone();
+        let unlinked = spawn_blocking(move || {
+            let mut conn = conn_pool.get()?;
+            let transaction = conn.transaction()?;
+            let files = transaction
+                .prepare("SELECT * FROM episode_file WHERE torrent_id = ?1")?
+                .query_map(params![torrent_id], |row| EpisodeFile::from_row(row, 0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut unlinked = 0;
+            for file in files {
+                if selected.contains(&file.file_index) {
+                    continue;
+                }
+                transaction.execute(
+                    "UPDATE episode SET torrent_id = NULL WHERE id = ?1 AND torrent_id = ?2",
+                    params![file.ep_id, torrent_id],
+                )?;
+                transaction.execute(
+                    "DELETE FROM episode_file WHERE ep_id = ?1",
+                    params![file.ep_id],
+                )?;
+                unlinked += 1;
+            }
+            transaction.commit()?;
+            KisaraResult::Ok(unlinked)
+        })
+        .await??;
+        self.links_changed();
+        info!(unlinked, "Unlinked unselected episode files");
+        Ok(())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn get_episode_file(&self, ep_id: i32) -> KisaraResult<Option<EpisodeFile>> {
+        info!("Fetching episode file");
+        let conn = self.conn_pool.get()?;
+        let query = "SELECT * FROM episode_file WHERE ep_id = ?1";
+        let file = spawn_blocking(move || {
+            let mut stmt = conn.prepare(query)?;
+            let result = stmt
+                .query_map(params![ep_id], |row| EpisodeFile::from_row(row, 0))?
+                .next()
+                .transpose()?;
+            KisaraResult::Ok(result)
+        })
+        .await??;
+        info!(?file, "Fetched episode file");
+        Ok(file)
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn set_torrent_upgrade(&self, upgrade: TorrentUpgrade) -> KisaraResult<()> {
+        info!("Setting torrent upgrade");
+        let conn = self.conn_pool.get()?;
+        let query = "INSERT OR REPLACE INTO torrent_upgrade (ep_id, torrent_id, delete_old) VALUES (?1, ?2, ?3)";
+        spawn_blocking(move || {
+            conn.execute(
+                query,
+                params![upgrade.ep_id, upgrade.torrent_id, upgrade.delete_old],
+            )?;
+            KisaraResult::Ok(())
+        })
+        .await??;
+        self.links_changed();
+        info!("Set torrent upgrade successfully");
+        Ok(())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn get_torrent_upgrades(&self) -> KisaraResult<Vec<TorrentUpgrade>> {
+        info!("Fetching torrent upgrades");
+        let conn = self.conn_pool.get()?;
+        let query = "SELECT * FROM torrent_upgrade";
+        let upgrades = spawn_blocking(move || {
+            let mut stmt = conn.prepare(query)?;
+            let result = stmt
+                .query_map(params![], |row| TorrentUpgrade::from_row(row, 0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            KisaraResult::Ok(result)
+        })
+        .await??;
+        info!(?upgrades, "Fetched torrent upgrades");
+        Ok(upgrades)
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn remove_torrent_upgrade(&self, ep_id: i32) -> KisaraResult<()> {
+        info!("Removing torrent upgrade");
+        let conn = self.conn_pool.get()?;
+        let query = "DELETE FROM torrent_upgrade WHERE ep_id = ?1";
+        spawn_blocking(move || {
+            conn.execute(query, params![ep_id])?;
+            KisaraResult::Ok(())
+        })
+        .await??;
+        self.links_changed();
+        info!("Removed torrent upgrade successfully");
+        Ok(())
+    }
+
+    /// Whether any episode still plays from the torrent.
+    #[instrument(level = "info", skip(self))]
+    pub async fn torrent_in_use(&self, torrent_id: String) -> KisaraResult<bool> {
+        info!("Checking whether torrent is in use");
+        let conn = self.conn_pool.get()?;
+        let query = "SELECT EXISTS(SELECT 1 FROM episode WHERE torrent_id = ?1)";
+        let in_use = spawn_blocking(move || {
+            let result: bool = conn.query_row(query, params![torrent_id], |row| row.get(0))?;
+            KisaraResult::Ok(result)
+        })
+        .await??;
+        info!(in_use, "Checked whether torrent is in use");
+        Ok(in_use)
+    }
+
+    #[instrument(level = "info", skip(self, record), fields(info_hash = record.info_hash))]
+    pub async fn save_torrent(&self, record: TorrentRecord) -> KisaraResult<()> {
+        info!("Saving torrent");
+        let conn = self.conn_pool.get()?;
+        let query =
+            "INSERT OR REPLACE INTO torrent (info_hash, magnet, torrent_bytes) VALUES (?1, ?2, ?3)";
+        spawn_blocking(move || {
+            conn.execute(
+                query,
+                params![record.info_hash, record.magnet, record.torrent_bytes],
+            )?;
+            KisaraResult::Ok(())
+        })
+        .await??;
+        info!("Saved torrent successfully");
+        Ok(())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn get_torrent_record(
+        &self,
+        info_hash: String,
+    ) -> KisaraResult<Option<TorrentRecord>> {
+        info!("Fetching torrent record");
+        let conn = self.conn_pool.get()?;
+        let query = "SELECT * FROM torrent WHERE info_hash = ?1";
+        let record = spawn_blocking(move || {
+            let mut stmt = conn.prepare(query)?;
+            let result = stmt
+                .query_map(params![info_hash], |row| TorrentRecord::from_row(row, 0))?
+                .next()
+                .transpose()?;
+            KisaraResult::Ok(result)
+        })
+        .await??;
+        info!(found = record.is_some(), "Fetched torrent record");
+        Ok(record)
+    }
+
+    /// Torrent ids still stored as session ids rather than info hashes.
+    #[instrument(level = "info", skip(self))]
+    pub async fn get_legacy_torrent_ids(&self) -> KisaraResult<Vec<String>> {
+        info!("Fetching legacy torrent IDs");
+        let conn = self.conn_pool.get()?;
+        let query = "SELECT torrent_id FROM episode WHERE torrent_id IS NOT NULL AND length(torrent_id) != 40 UNION SELECT torrent_id FROM episode_file WHERE length(torrent_id) != 40 UNION SELECT torrent_id FROM torrent_upgrade WHERE length(torrent_id) != 40";
+        let torrent_ids = spawn_blocking(move || {
+            let mut stmt = conn.prepare(query)?;
+            let result = stmt
+                .query_map(params![], |row| row.get(0))?
+                .collect::<Result<Vec<String>, _>>()?;
+            KisaraResult::Ok(result)
+        })
+        .await??;
+        info!(?torrent_ids, "Fetched legacy torrent IDs");
+        Ok(torrent_ids)
+    }
+
+    /// Points everything referring to a torrent by its session id to its record instead.
+    #[instrument(level = "info", skip(self, record), fields(info_hash = record.info_hash))]
+    pub async fn migrate_torrent_id(
+        &self,
+        torrent_id: String,
+        record: TorrentRecord,
+    ) -> KisaraResult<()> {
+        info!("Migrating torrent ID to info hash");
+        let conn_pool = self.conn_pool.clone();
+        let result: KisaraResult<()> = spawn_blocking(move || {
+            let mut conn = conn_pool.get()?;
+            let transaction = conn.transaction()?;
+            transaction.execute(
+                "INSERT OR REPLACE INTO torrent (info_hash, magnet, torrent_bytes) VALUES (?1, ?2, ?3)",
+                params![record.info_hash, record.magnet, record.torrent_bytes],
+            )?;
+            for table in ["episode", "episode_file", "torrent_upgrade"] {
+                transaction.execute(
+                    &format!("UPDATE {} SET torrent_id = ?1 WHERE torrent_id = ?2", table),
+                    params![record.info_hash, torrent_id],
+                )?;
+            }
+            transaction.commit()?;
+            Ok(())
+        })
+        .await?;
+        self.links_changed();
+        info!("Migrated torrent ID to info hash");
+        result
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn get_torrent_controls(&self) -> KisaraResult<Vec<TorrentControl>> {
+        info!("Fetching torrent controls");
+        let conn = self.conn_pool.get()?;
+        let query = "SELECT * FROM torrent_control";
+        let controls = spawn_blocking(move || {
+            let mut stmt = conn.prepare(query)?;
+            let result = stmt
+                .query_map(params![], |row| TorrentControl::from_row(row, 0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            KisaraResult::Ok(result)
+        })
+        .await??;
+        info!(?controls, "Fetched torrent controls");
+        Ok(controls)
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn set_torrent_priority(&self, info_hash: String, priority: i32) -> KisaraResult<()> {
+        info!("Setting torrent priority");
+        let conn = self.conn_pool.get()?;
+        let query = "INSERT INTO torrent_control (info_hash, priority) VALUES (?1, ?2) ON CONFLICT (info_hash) DO UPDATE SET priority = excluded.priority";
+        spawn_blocking(move || {
+            conn.execute(query, params![info_hash, priority])?;
+            KisaraResult::Ok(())
+        })
+        .await??;
+        info!("Set torrent priority successfully");
+        Ok(())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn set_torrent_paused(&self, info_hash: String, paused: bool) -> KisaraResult<()> {
+        info!("Setting torrent paused");
+        let conn = self.conn_pool.get()?;
+        let query = "INSERT INTO torrent_control (info_hash, paused) VALUES (?1, ?2) ON CONFLICT (info_hash) DO UPDATE SET paused = excluded.paused";
+        spawn_blocking(move || {
+            conn.execute(query, params![info_hash, paused])?;
+            KisaraResult::Ok(())
+        })
+        .await??;
+        info!("Set torrent paused successfully");
+        Ok(())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn get_torrent_seedings(&self) -> KisaraResult<Vec<TorrentSeeding>> {
+        info!("Fetching torrent seedings");
+        let conn = self.conn_pool.get()?;
+        let query = "SELECT * FROM torrent_seeding";
+        let seedings = spawn_blocking(move || {
+            let mut stmt = conn.prepare(query)?;
+            let result = stmt
+                .query_map(params![], |row| TorrentSeeding::from_row(row, 0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            KisaraResult::Ok(result)
+        })
+        .await??;
+        info!(?seedings, "Fetched torrent seedings");
+        Ok(seedings)
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn save_torrent_seeding(&self, seeding: TorrentSeeding) -> KisaraResult<()> {
+        info!("Saving torrent seeding");
+        let conn = self.conn_pool.get()?;
+        let query = "INSERT OR REPLACE INTO torrent_seeding (info_hash, since, uploaded_bytes, stopped) VALUES (?1, ?2, ?3, ?4)";
+        spawn_blocking(move || {
+            conn.execute(
+                query,
+                params![
+                    seeding.info_hash,
+                    seeding.since,
+                    seeding.uploaded_bytes,
+                    seeding.stopped
+                ],
+            )?;
+            KisaraResult::Ok(())
+        })
+        .await??;
+        info!("Saved torrent seeding successfully");
+        Ok(())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn get_anime_seeding_policy(
+        &self,
+        anime_id: i32,
+    ) -> KisaraResult<Option<SeedingPolicy>> {
+        info!("Fetching anime seeding policy");
+        let conn = self.conn_pool.get()?;
+        let query = "SELECT max_ratio, max_hours FROM anime_seeding WHERE anime_id = ?1";
+        let policy = spawn_blocking(move || {
+            let mut stmt = conn.prepare(query)?;
+            let result = stmt
+                .query_map(params![anime_id], |row| SeedingPolicy::from_row(row, 0))?
+                .next()
+                .transpose()?;
+            KisaraResult::Ok(result)
+        })
+        .await??;
+        info!(?policy, "Fetched anime seeding policy");
+        Ok(policy)
+    }
+
+    /// Overrides the global seeding policy for an anime, `None` goes back to the global one.
+    #[instrument(level = "info", skip(self))]
+    pub async fn set_anime_seeding_policy(
+        &self,
+        anime_id: i32,
+        policy: Option<SeedingPolicy>,
+    ) -> KisaraResult<()> {
+        info!("Setting anime seeding policy");
+        let conn = self.conn_pool.get()?;
+        spawn_blocking(move || {
+            if let Some(policy) = policy {
+                conn.execute(
+                    "INSERT OR REPLACE INTO anime_seeding (anime_id, max_ratio, max_hours) VALUES (?1, ?2, ?3)",
+                    params![anime_id, policy.max_ratio, policy.max_hours],
+                )?;
+            } else {
+                conn.execute(
+                    "DELETE FROM anime_seeding WHERE anime_id = ?1",
+                    params![anime_id],
+                )?;
+            }
+            KisaraResult::Ok(())
+        })
+        .await??;
+        info!("Set anime seeding policy successfully");
+        Ok(())
+    }
+
+    /// Episodes that have a torrent, with their anime and the length of their video if it is
+    /// known.
+    #[instrument(level = "info", skip(self))]
+    pub async fn get_episodes_with_torrent(
+        &self,
+    ) -> KisaraResult<Vec<(Anime, Episode, Option<u32>)>> {
+        info!("Fetching episodes with torrent");
+        let conn = self.conn_pool.get()?;
+        let query = "SELECT a.*, e.*, d.duration FROM episode e JOIN anime a ON a.id = e.anime_id LEFT JOIN episode_duration d ON d.ep_id = e.id WHERE e.torrent_id IS NOT NULL";
+        let episodes = spawn_blocking(move || {
+            let mut stmt = conn.prepare(query)?;
+            let result = stmt
+                .query_map(params![], |row| {
+                    let anime = Anime::from_row(row, 0)?;
+                    let episode = Episode::from_row(row, 7)?;
+                    let duration = row.get(17)?;
+                    Ok((anime, episode, duration))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            KisaraResult::Ok(result)
+        })
+        .await??;
+        info!(count = episodes.len(), "Fetched episodes with torrent");
+        Ok(episodes)
+    }
 }
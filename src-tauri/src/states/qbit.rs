@@ -1,65 +1,321 @@
 use std::{
     borrow::Cow,
     cell::RefCell,
-    collections::{BinaryHeap, HashMap},
-    path::PathBuf,
-    sync::Arc,
+    collections::{BinaryHeap, HashMap, HashSet},
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    pin::pin,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
 };
 
+use chrono::Local;
+use futures::future::{Either, select};
 use infer::MatcherType;
 use librqbit::{
-    AddTorrent, ManagedTorrent, Session, SessionOptions, SessionPersistenceConfig, TorrentStats,
-    api::TorrentIdOrHash,
+    AddTorrent, AddTorrentOptions, AddTorrentResponse, Magnet, ManagedTorrent, Session,
+    SessionOptions, SessionPersistenceConfig, TorrentStats, TorrentStatsState,
+    api::TorrentIdOrHash, file_info::FileInfo, limits::LimitsConfig,
 };
 use serde::Serialize;
-use tauri::{AppHandle, async_runtime::spawn};
+use tauri::{
+    AppHandle, Manager,
+    async_runtime::{JoinHandle, spawn},
+};
 use tauri_plugin_notification::NotificationExt;
-use tracing::{debug, info, info_span, instrument, trace};
+use tokio::io::{AsyncRead, AsyncSeek};
+use tracing::{debug, info, info_span, instrument, trace, warn};
 
 use crate::{
+    TRAY_ID,
+    data::torrent_record::TorrentRecord,
     error::{KisaraError, KisaraResult},
-    events::{Event, TorrentComplete, TorrentInit},
-    utils::video::get_video_duration,
+    events::{
+        DiskSpaceLow, Event, TorrentComplete, TorrentError, TorrentInit, TorrentProgress,
+        TorrentProgressEntry, TorrentRemoved, TorrentState,
+    },
+    tasks::stream::StreamServer,
+    utils::{
+        disk::available_space,
+        release_name::ReleaseInfo,
+        relocate::move_downloads,
+        video::{get_video_duration, is_video_file},
+    },
+};
+
+use super::{
+    DatabaseHelperState,
+    config::{BandwidthLimits, DownloadConfig},
 };
 
-use super::config::DownloadConfig;
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+const fn kib_to_bps(kib: u32) -> Option<NonZeroU32> {
+    NonZeroU32::new(kib.saturating_mul(1024))
+}
 
 pub struct QbitClient {
     session: Arc<Session>,
     app: Option<AppHandle>,
     download_folder: PathBuf,
+    limits: BandwidthLimits,
+    /// Bytes kept free on the download volume.
+    disk_reserve: u64,
+    progress_reporter: Option<JoinHandle<()>>,
+    /// Whether the session is stopped for its downloads to move.
+    relocating: bool,
+}
+
+/// Downloads on their way to another folder, see [`QbitClient::start_relocation`].
+pub struct Relocation {
+    old_folder: PathBuf,
+    new_folder: PathBuf,
+    /// Paths inside each torrent, by info hash.
+    files: Vec<(String, Vec<PathBuf>)>,
+    /// Torrents that were running, to resume once the session is open again.
+    paused: Vec<String>,
+}
+
+impl Relocation {
+    pub fn new_folder(&self) -> &Path {
+        &self.new_folder
+    }
+
+    /// Moves the files and the session, reporting progress with `RelocationProgress`. On
+    /// failure, what was moved is moved back. Blocks until done.
+    pub fn move_downloads(&self, app: Option<&AppHandle>) -> KisaraResult<()> {
+        info!("Moving downloads");
+        move_downloads(&self.old_folder, &self.new_folder, &self.files, app)
+    }
 }
 
 impl QbitClient {
     pub async fn new(download_config: DownloadConfig) -> KisaraResult<Self> {
-        let mut session_opts: SessionOptions = Default::default();
-        let persistence_opts = SessionPersistenceConfig::Json {
-            folder: Some(PathBuf::from(&download_config.download_path).join("session")),
-        };
-        session_opts.persistence = Some(persistence_opts);
         let download_path = PathBuf::from(&download_config.download_path);
         let download_path = std::fs::canonicalize(download_path.clone())
             .map_err(|_| KisaraError::InvalidPath(download_path))?;
-        let session =
-            Session::new_with_opts(PathBuf::from(download_config.download_path), session_opts)
-                .await?;
+        let limits = download_config.limits_at(Local::now().naive_local());
+        let session = Self::open_session(&download_path, limits).await?;
         let s = Self {
-            session: Arc::clone(&session),
+            session,
             app: None,
             download_folder: download_path,
+            limits,
+            disk_reserve: u64::from(download_config.disk_reserve_gib) * GIB,
+            progress_reporter: None,
+            relocating: false,
+        };
+        s.wait_unfinished();
+        Ok(s)
+    }
+
+    async fn open_session(
+        download_path: &Path,
+        limits: BandwidthLimits,
+    ) -> KisaraResult<Arc<Session>> {
+        let mut session_opts: SessionOptions = Default::default();
+        let persistence_opts = SessionPersistenceConfig::Json {
+            folder: Some(download_path.join("session")),
         };
-        session.with_torrents(|torrents| {
+        session_opts.persistence = Some(persistence_opts);
+        session_opts.ratelimits = LimitsConfig {
+            upload_bps: limits.upload_kib.and_then(kib_to_bps),
+            download_bps: limits.download_kib.and_then(kib_to_bps),
+        };
+        Ok(Session::new_with_opts(download_path.to_path_buf(), session_opts).await?)
+    }
+
+    fn wait_unfinished(&self) {
+        self.session.with_torrents(|torrents| {
             torrents.for_each(|(_, t)| {
                 if !t.stats().finished {
-                    s.start_new_wait_complete(t);
+                    self.start_new_wait_complete(t);
                 }
             });
         });
-        Ok(s)
+    }
+
+    /// Applies new rate limits. librqbit 8 has no public API to change them on a running
+    /// session, it only takes them when it starts, so the session is restarted and the
+    /// torrents come back from its persistence. If the new session fails to open, the old
+    /// limits are opened again. Returns whether the session was restarted.
+    #[instrument(level = "info", skip(self))]
+    pub async fn set_limits(&mut self, limits: BandwidthLimits) -> KisaraResult<bool> {
+        if limits == self.limits {
+            return Ok(false);
+        }
+        // the session is stopped for the move, reopening it would write into the old folder
+        if self.relocating {
+            return Err(KisaraError::Relocating);
+        }
+        info!("Restarting session with new limits");
+        // a stopped session has no torrents, which would be reported as removed
+        if let Some(reporter) = self.progress_reporter.take() {
+            reporter.abort();
+        }
+        self.session.stop().await;
+        match Self::open_session(&self.download_folder, limits).await {
+            Ok(session) => {
+                self.session = session;
+                self.limits = limits;
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to open session with new limits, keeping the old ones");
+                self.session = Self::open_session(&self.download_folder, self.limits).await?;
+            }
+        }
+        self.wait_unfinished();
+        self.start_progress_reporter();
+        Ok(true)
+    }
+
+    /// Stops the session to move the downloads to `new_folder`, pausing the torrents first.
+    /// Until [`Self::finish_relocation`], torrents cannot be added or looked up, so the
+    /// files can be moved with [`Relocation::move_downloads`] without holding the client.
+    /// `None` if the downloads are in `new_folder` already.
+    #[instrument(level = "info", skip(self))]
+    pub async fn start_relocation(
+        &mut self,
+        new_folder: &Path,
+    ) -> KisaraResult<Option<Relocation>> {
+        std::fs::create_dir_all(new_folder)?;
+        let new_folder = std::fs::canonicalize(new_folder)
+            .map_err(|_| KisaraError::InvalidPath(new_folder.to_path_buf()))?;
+        if new_folder == self.download_folder {
+            return Ok(None);
+        }
+        // the player would lose the file it reads mid-move
+        if self
+            .app
+            .as_ref()
+            .and_then(Manager::try_state::<StreamServer>)
+            .is_some_and(|server| server.is_streaming())
+        {
+            return Err(KisaraError::Streaming);
+        }
+
+        let torrents = RefCell::new(Vec::new());
+        self.session.with_torrents(|t| {
+            torrents.replace(t.map(|entry| Arc::clone(entry.1)).collect::<Vec<_>>());
+        });
+        let mut paused = Vec::new();
+        let mut files = Vec::new();
+        for torrent in torrents.into_inner() {
+            let info_hash = torrent.info_hash().as_string();
+            if !torrent.is_paused() {
+                match self.session.pause(&torrent).await {
+                    Ok(()) => paused.push(info_hash.clone()),
+                    Err(e) => warn!(error = %e, info_hash, "Failed to pause torrent"),
+                }
+            }
+            if let Some(metadata) = torrent.metadata.load().as_ref() {
+                let names = metadata
+                    .file_infos
+                    .iter()
+                    .map(|file| file.relative_filename.clone())
+                    .collect();
+                files.push((info_hash, names));
+            }
+        }
+        if let Some(reporter) = self.progress_reporter.take() {
+            reporter.abort();
+        }
+        self.session.stop().await;
+        self.relocating = true;
+
+        Ok(Some(Relocation {
+            old_folder: self.download_folder.clone(),
+            new_folder,
+            files,
+            paused,
+        }))
+    }
+
+    /// Reopens the session in the new folder if the downloads `moved`, else where it was,
+    /// and resumes the torrents that were running. A torrent failing to resume does not
+    /// keep the others from it. Returns the folder the downloads are in now.
+    #[instrument(level = "info", skip(self, relocation))]
+    pub async fn finish_relocation(
+        &mut self,
+        relocation: Relocation,
+        moved: bool,
+    ) -> KisaraResult<PathBuf> {
+        if moved {
+            self.download_folder = relocation.new_folder;
+        }
+        self.relocating = false;
+        // stopped already, but stopping again makes sure no session is left running
+        self.session.stop().await;
+        self.session = Self::open_session(&self.download_folder, self.limits).await?;
+        self.wait_unfinished();
+        self.start_progress_reporter();
+
+        let mut failed = Vec::new();
+        for info_hash in relocation.paused {
+            if let Err(e) = self.resume_torrent(&info_hash).await {
+                warn!(error = %e, info_hash, "Failed to resume torrent");
+                failed.push(info_hash);
+            }
+        }
+        if !failed.is_empty() {
+            return Err(KisaraError::Any(format!(
+                "Failed to resume torrents: {}",
+                failed.join(", ")
+            )));
+        }
+        Ok(self.download_folder.clone())
+    }
+
+    /// Whether the downloads are moving, see [`Self::start_relocation`].
+    pub const fn is_relocating(&self) -> bool {
+        self.relocating
+    }
+
+    pub const fn set_disk_reserve(&mut self, reserve_gib: u32) {
+        self.disk_reserve = reserve_gib as u64 * GIB;
+    }
+
+    /// Bytes torrents may still take on the download volume, after the reserve.
+    pub fn available_space(&self) -> KisaraResult<u64> {
+        Ok(available_space(&self.download_folder)?.saturating_sub(self.disk_reserve))
+    }
+
+    /// Bytes a torrent still has to download, of the files selected.
+    pub fn remaining_bytes(&self, torrent_id: &str) -> KisaraResult<u64> {
+        let stats = self.get_torrent(torrent_id)?.stats();
+        Ok(stats.total_bytes.saturating_sub(stats.progress_bytes))
     }
 
     pub fn set_app(&mut self, app: AppHandle) {
         self.app = Some(app);
+        self.start_progress_reporter();
+    }
+
+    /// Pushes the progress of the session's torrents to the frontend every
+    /// `PROGRESS_INTERVAL`, in place of it polling.
+    fn start_progress_reporter(&mut self) {
+        let Some(app) = self.app.clone() else {
+            return;
+        };
+        if let Some(reporter) = self.progress_reporter.take() {
+            reporter.abort();
+        }
+        let session = Arc::clone(&self.session);
+        self.progress_reporter = Some(spawn(async move {
+            let mut reporter = ProgressReporter::default();
+            let mut interval = tokio::time::interval(PROGRESS_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = reporter.report(&app, &session).await {
+                    warn!(error = %e, "Failed to report torrent progress");
+                }
+            }
+        }));
     }
 
     #[instrument(level = "info", skip(self,torrent),fields(torrent_id = torrent.id()))]
@@ -68,14 +324,20 @@ impl QbitClient {
         spawn({
             let span = info_span!("torrent_init", torrent_id = torrent.id());
             let app = self.app.clone();
+            let session = Arc::clone(&self.session);
 
             async move {
                 let _enter = span.enter();
-                torrent.wait_until_initialized().await?;
+                let Some(initialized) =
+                    until_stopped(&session, torrent.wait_until_initialized()).await
+                else {
+                    return Ok(());
+                };
+                initialized?;
 
                 info!("Torrent initialized");
                 if let Some(app) = app {
-                    TorrentInit::new(torrent.id().to_string()).emit(&app)?;
+                    TorrentInit::new(torrent.info_hash().as_string()).emit(&app)?;
                 }
 
                 KisaraResult::Ok(())
@@ -90,14 +352,19 @@ impl QbitClient {
             let span = info_span!("torrent_complete", torrent_id = torrent.id());
             let torrent = Arc::clone(torrent);
             let app = self.app.clone();
+            let session = Arc::clone(&self.session);
 
             async move {
                 let _enter = span.enter();
-                torrent.wait_until_completed().await?;
+                let Some(completed) = until_stopped(&session, torrent.wait_until_completed()).await
+                else {
+                    return Ok(());
+                };
+                completed?;
 
                 info!("Torrent completed");
                 if let Some(app) = app {
-                    TorrentComplete::new(torrent.id().to_string()).emit(&app)?;
+                    TorrentComplete::new(torrent.info_hash().as_string()).emit(&app)?;
                     let _ = app
                         .notification()
                         .builder()
@@ -114,20 +381,157 @@ impl QbitClient {
         });
     }
 
+    /// Adds a torrent by magnet and returns what is needed to add it again, with the info
+    /// hash torrents are referred to by. A new torrent that would not fit on the download
+    /// volume is removed again and `KisaraError::InsufficientDiskSpace` returned.
     #[instrument(level = "info", skip(self))]
-    pub async fn add_torrent(&self, magnet: &str) -> KisaraResult<String> {
+    pub async fn add_torrent(&self, magnet: &str) -> KisaraResult<TorrentRecord> {
         info!("Adding torrent");
+        let managed = Magnet::parse(magnet)
+            .ok()
+            .and_then(|magnet| magnet.as_id20())
+            .is_some_and(|id| self.torrent_exists(&id.as_string()));
+        if managed {
+            let handle = self
+                .add(AddTorrent::Url(Cow::Borrowed(magnet)), None)
+                .await?;
+            return Ok(Self::record(&handle, magnet.to_owned()));
+        }
+
+        // its size is only known once added, so it starts once it is known to fit
+        let opts = AddTorrentOptions {
+            paused: true,
+            ..Default::default()
+        };
+        let handle = self
+            .add(AddTorrent::Url(Cow::Borrowed(magnet)), Some(opts))
+            .await?;
+        let unpaused = self.unpause_checked(&handle).await;
+        if let Err(KisaraError::InsufficientDiskSpace { .. }) = unpaused {
+            info!("Removing torrent that does not fit");
+            self.session
+                .delete(TorrentIdOrHash::Id(handle.id()), false)
+                .await?;
+        }
+        unpaused?;
+        Ok(Self::record(&handle, magnet.to_owned()))
+    }
+
+    /// Fetches the metadata of a torrent without adding it, so it is known by its info hash
+    /// before the session has it.
+    #[instrument(level = "info", skip(self))]
+    pub async fn resolve_torrent(&self, magnet: &str) -> KisaraResult<TorrentRecord> {
+        info!("Resolving torrent");
+        let opts = AddTorrentOptions {
+            list_only: true,
+            ..Default::default()
+        };
+        let response = self
+            .session
+            .add_torrent(AddTorrent::Url(Cow::Borrowed(magnet)), Some(opts))
+            .await?;
+        Ok(match response {
+            AddTorrentResponse::ListOnly(listed) => TorrentRecord {
+                info_hash: listed.info_hash.as_string(),
+                magnet: magnet.to_owned(),
+                torrent_bytes: Some(listed.torrent_bytes.to_vec()),
+            },
+            AddTorrentResponse::AlreadyManaged(_, handle)
+            | AddTorrentResponse::Added(_, handle) => Self::record(&handle, magnet.to_owned()),
+        })
+    }
+
+    /// Adds a resolved torrent without starting it.
+    #[instrument(level = "info", skip(self, record), fields(info_hash = record.info_hash))]
+    pub async fn add_torrent_paused(&self, record: &TorrentRecord) -> KisaraResult<()> {
+        info!("Adding paused torrent");
+        let opts = AddTorrentOptions {
+            paused: true,
+            ..Default::default()
+        };
+        self.add(Self::source(record), Some(opts)).await?;
+        Ok(())
+    }
+
+    /// Adds a torrent the session lost again, paused. What it has left is only known once
+    /// its files are checked, after which the queue starts it if it fits.
+    #[instrument(level = "info", skip(self, record), fields(info_hash = record.info_hash))]
+    pub async fn restore_torrent(&self, record: &TorrentRecord) -> KisaraResult<()> {
+        info!("Restoring torrent");
+        let opts = AddTorrentOptions {
+            paused: true,
+            ..Default::default()
+        };
+        self.add(Self::source(record), Some(opts)).await?;
+        Ok(())
+    }
+
+    /// What to add a recorded torrent from, its file if stored, else its magnet.
+    fn source(record: &TorrentRecord) -> AddTorrent<'_> {
+        record.torrent_bytes.as_ref().map_or_else(
+            || AddTorrent::Url(Cow::Borrowed(&record.magnet)),
+            |bytes| AddTorrent::TorrentFileBytes(bytes.clone().into()),
+        )
+    }
+
+    async fn add(
+        &self,
+        add: AddTorrent<'_>,
+        opts: Option<AddTorrentOptions>,
+    ) -> KisaraResult<Arc<ManagedTorrent>> {
+        if self.relocating {
+            return Err(KisaraError::Relocating);
+        }
         let handle = self
             .session
-            .add_torrent(AddTorrent::Url(Cow::Borrowed(magnet)), None)
+            .add_torrent(add, opts)
             .await?
             .into_handle()
             .expect("We know this is a valid torrent");
 
         self.start_new_wait_init(Arc::clone(&handle));
         self.start_new_wait_complete(&handle);
+        Ok(handle)
+    }
 
-        Ok(handle.id().to_string())
+    fn record(torrent: &ManagedTorrent, magnet: String) -> TorrentRecord {
+        TorrentRecord {
+            info_hash: torrent.info_hash().as_string(),
+            magnet,
+            torrent_bytes: torrent
+                .metadata
+                .load()
+                .as_ref()
+                .map(|metadata| metadata.torrent_bytes.to_vec()),
+        }
+    }
+
+    /// The record of a torrent added before info hashes were stored, looked up by the
+    /// session id it was stored with, with a magnet made from its hash.
+    pub fn get_legacy_torrent_record(&self, torrent_id: &str) -> KisaraResult<TorrentRecord> {
+        let torrent = torrent_id
+            .parse::<usize>()
+            .ok()
+            .and_then(|id| self.session.get(TorrentIdOrHash::Id(id)))
+            .ok_or_else(|| KisaraError::NoSuchTorrent(torrent_id.to_owned()))?;
+        let info_hash = torrent.info_hash().as_string();
+        Ok(Self::record(
+            &torrent,
+            format!("magnet:?xt=urn:btih:{}", info_hash),
+        ))
+    }
+
+    /// Looks a torrent up by info hash. Session ids are not accepted, they may refer to
+    /// another torrent once the session lost the one they were given for.
+    fn get_torrent(&self, torrent_id: &str) -> KisaraResult<Arc<ManagedTorrent>> {
+        if self.relocating {
+            return Err(KisaraError::Relocating);
+        }
+        match TorrentIdOrHash::parse(torrent_id) {
+            Ok(hash @ TorrentIdOrHash::Hash(_)) => self.session.get(hash),
+            _ => None,
+        }
+        .ok_or_else(|| KisaraError::NoSuchTorrent(torrent_id.to_owned()))
     }
 
     #[instrument(level = "trace", skip(self))]
@@ -142,13 +546,15 @@ impl QbitClient {
         count
     }
 
-    pub fn get_torrent_stats(&self) -> HashMap<usize, ManagedTorrentInfo> {
-        let torrents = RefCell::new(HashMap::new());
+    /// Stats of every torrent by info hash, in the order they were added.
+    pub fn get_torrent_stats(&self) -> Vec<(String, ManagedTorrentInfo)> {
+        let torrents = RefCell::new(Vec::new());
         self.session.with_torrents(|t| {
             torrents.replace(
                 t.map(|(id, t)| {
                     (
                         id,
+                        t.info_hash().as_string(),
                         ManagedTorrentInfo {
                             name: t.name(),
                             stats: t.stats(),
@@ -158,11 +564,149 @@ impl QbitClient {
                 .collect(),
             );
         });
-        torrents.into_inner()
+        let mut torrents = torrents.into_inner();
+        torrents.sort_by_key(|t| t.0);
+        torrents.into_iter().map(|t| (t.1, t.2)).collect()
     }
 
+    /// Index and path relative to the torrent of every file in a torrent.
+    pub fn get_file_names(&self, torrent_id: &str) -> KisaraResult<Vec<(usize, String)>> {
+        let torrent = self.get_torrent(torrent_id)?;
+        let metadata = torrent
+            .metadata
+            .load()
+            .clone()
+            .ok_or(KisaraError::NoSuchTorrent(torrent_id.to_owned()))?;
+        Ok(metadata
+            .file_infos
+            .iter()
+            .enumerate()
+            .map(|(index, file)| (index, file.relative_filename.to_string_lossy().into_owned()))
+            .collect())
+    }
+
+    /// Every file in a torrent and whether it is downloaded.
+    pub fn get_torrent_files(&self, torrent_id: &str) -> KisaraResult<Vec<TorrentFile>> {
+        let torrent = self.get_torrent(torrent_id)?;
+        let metadata = torrent
+            .metadata
+            .load()
+            .clone()
+            .ok_or(KisaraError::NoSuchTorrent(torrent_id.to_owned()))?;
+        let only_files = torrent.only_files();
+        Ok(metadata
+            .file_infos
+            .iter()
+            .enumerate()
+            .map(|(index, file)| TorrentFile {
+                index,
+                name: file.relative_filename.to_string_lossy().into_owned(),
+                size: file.len,
+                selected: only_files.as_ref().is_none_or(|only| only.contains(&index)),
+            })
+            .collect())
+    }
+
+    /// Downloads only the given files of a torrent. Files already downloaded stay on disk.
+    #[instrument(level = "info", skip(self))]
+    pub async fn select_files(&self, torrent_id: &str, files: &HashSet<usize>) -> KisaraResult<()> {
+        let torrent = self.get_torrent(torrent_id)?;
+        self.session.update_only_files(&torrent, files).await?;
+        Ok(())
+    }
+
+    /// Resolves once the torrent has checked its files, before which the files to download
+    /// cannot be changed.
+    pub fn wait_until_initialized(
+        &self,
+        torrent_id: &str,
+    ) -> KisaraResult<impl Future<Output = KisaraResult<()>> + Send + 'static> {
+        let torrent = self.get_torrent(torrent_id)?;
+        Ok(async move {
+            torrent.wait_until_initialized().await?;
+            KisaraResult::Ok(())
+        })
+    }
+
+    /// Picks the video of an episode by file name alone, for torrents whose files cannot be
+    /// probed yet. Otherwise works like `get_files`, with the largest video standing in for
+    /// the longest.
+    pub fn find_video_file(
+        &self,
+        torrent_id: &str,
+        ep: Option<i32>,
+        file: Option<usize>,
+    ) -> KisaraResult<usize> {
+        if let Some(file) = file {
+            return Ok(file);
+        }
+        let torrent = self.get_torrent(torrent_id)?;
+        let metadata = torrent
+            .metadata
+            .load()
+            .clone()
+            .ok_or(KisaraError::NoSuchTorrent(torrent_id.to_owned()))?;
+        let videos = metadata
+            .file_infos
+            .iter()
+            .enumerate()
+            .filter(|entry| is_video_file(&entry.1.relative_filename))
+            .collect::<Vec<_>>();
+        let matching = ep.and_then(|ep| {
+            videos.iter().find(|entry| {
+                entry
+                    .1
+                    .relative_filename
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| ReleaseInfo::parse(name).contains_episode(ep))
+            })
+        });
+        matching
+            .or_else(|| videos.iter().max_by_key(|entry| entry.1.len))
+            .map(|entry| entry.0)
+            .ok_or(KisaraError::NoVideoFoundInTorrent(torrent_id.to_owned()))
+    }
+
+    /// Where a file of a torrent is, or will be once downloaded.
+    pub fn get_file_path(&self, torrent_id: &str, file: usize) -> KisaraResult<String> {
+        let torrent = self.get_torrent(torrent_id)?;
+        let metadata = torrent
+            .metadata
+            .load()
+            .clone()
+            .ok_or(KisaraError::NoSuchTorrent(torrent_id.to_owned()))?;
+        let info = metadata
+            .file_infos
+            .get(file)
+            .ok_or(KisaraError::NoSuchTorrent(torrent_id.to_owned()))?;
+        self.path_string(&info.relative_filename)
+    }
+
+    /// Reads a file of a torrent as it downloads, the pieces being read are fetched first.
+    /// Also returns the length of the file.
+    pub fn open_stream(
+        &self,
+        torrent_id: &str,
+        file: usize,
+    ) -> KisaraResult<(impl AsyncRead + AsyncSeek + Unpin + Send + use<>, u64)> {
+        let stream = self.get_torrent(torrent_id)?.stream(file)?;
+        let len = stream.len();
+        Ok((stream, len))
+    }
+
+    /// Returns the video to play and the subtitle files in a torrent.
+    ///
+    /// With `file`, the index of an episode's file in a batch, that file is the video and
+    /// only subtitles named after it are returned. Otherwise the video whose name carries
+    /// episode `ep` wins, or else the longest one.
     // #[instrument(level = "info", skip(self))]
-    pub async fn get_files(&self, torrent_id: &str) -> KisaraResult<(String, Vec<String>)> {
+    pub async fn get_files(
+        &self,
+        torrent_id: &str,
+        ep: Option<i32>,
+        file: Option<usize>,
+    ) -> KisaraResult<(String, Vec<String>)> {
         struct VideoTmp {
             pub path: String,
             pub duration: f64,
@@ -190,20 +734,19 @@ impl QbitClient {
             }
         }
 
-        // parse torrent_id to usize
-        let torrent_id: usize = torrent_id.parse()?;
-        let torrent = self
-            .session
-            .get(TorrentIdOrHash::Id(torrent_id))
-            .ok_or(KisaraError::NoSuchTorrent(torrent_id.to_string()))?;
+        let torrent = self.get_torrent(torrent_id)?;
         let files = &torrent
             .metadata
             .load()
             .clone()
-            .ok_or(KisaraError::NoSuchTorrent(torrent_id.to_string()))?
+            .ok_or(KisaraError::NoSuchTorrent(torrent_id.to_owned()))?
             .file_infos;
         debug!(?files, "Files in torrent");
 
+        if let Some(file) = file.and_then(|i| files.get(i)) {
+            return self.get_batch_files(&file.relative_filename, files);
+        }
+
         let mut videos = BinaryHeap::new();
         let mut subtitles = Vec::new();
 
@@ -237,9 +780,21 @@ impl QbitClient {
             }
         }
 
-        let video = videos
-            .pop()
-            .ok_or(KisaraError::NoVideoFoundInTorrent(torrent_id.to_string()))?;
+        let mut videos = videos.into_sorted_vec();
+        let matching = ep.and_then(|ep| {
+            videos.iter().rposition(|v| {
+                Path::new(&v.path)
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| ReleaseInfo::parse(name).contains_episode(ep))
+            })
+        });
+        let video = match matching {
+            Some(i) => videos.swap_remove(i),
+            None => videos
+                .pop()
+                .ok_or(KisaraError::NoVideoFoundInTorrent(torrent_id.to_owned()))?,
+        };
 
         let video_path = video.path;
         info!(?video_path, "Video path");
@@ -248,20 +803,303 @@ impl QbitClient {
         Ok((video_path, subtitles))
     }
 
+    /// The video of one episode in a batch, and the subtitles whose names start like its.
+    fn get_batch_files(
+        &self,
+        video: &Path,
+        files: &[FileInfo],
+    ) -> KisaraResult<(String, Vec<String>)> {
+        let stem = video
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        let subtitles = files
+            .iter()
+            .map(|file| &file.relative_filename)
+            .filter(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| matches!(ext, "srt" | "sub" | "ass" | "vtt"))
+                    && path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.starts_with(stem))
+            })
+            .map(|path| self.path_string(path))
+            .collect::<KisaraResult<Vec<_>>>()?;
+        let video = self.path_string(video)?;
+        info!(?video, ?subtitles, "Batch episode files");
+        Ok((video, subtitles))
+    }
+
+    fn path_string(&self, relative: &Path) -> KisaraResult<String> {
+        let path = self.download_folder.join(relative);
+        path.to_str()
+            .map(ToOwned::to_owned)
+            .ok_or(KisaraError::InvalidPath(path.clone()))
+    }
+
+    pub fn is_finished(&self, torrent_id: &str) -> KisaraResult<bool> {
+        Ok(self.get_torrent(torrent_id)?.stats().finished)
+    }
+
+    /// Info hashes of the torrents still downloading, in the order they were added, and
+    /// whether each is paused.
+    pub fn get_unfinished_torrents(&self) -> Vec<(String, bool)> {
+        let torrents = RefCell::new(Vec::new());
+        self.session.with_torrents(|t| {
+            torrents.replace(
+                t.filter(|entry| !entry.1.stats().finished)
+                    .map(|(id, t)| (id, t.info_hash().as_string(), t.is_paused()))
+                    .collect(),
+            );
+        });
+        let mut torrents = torrents.into_inner();
+        torrents.sort_by_key(|t| t.0);
+        torrents.into_iter().map(|t| (t.1, t.2)).collect()
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn pause_torrent(&self, torrent_id: &str) -> KisaraResult<()> {
+        let torrent = self.get_torrent(torrent_id)?;
+        if !torrent.is_paused() {
+            info!("Pausing torrent");
+            self.session.pause(&torrent).await?;
+        }
+        Ok(())
+    }
+
+    #[instrument(level = "info", skip(self))]
+    pub async fn resume_torrent(&self, torrent_id: &str) -> KisaraResult<()> {
+        let torrent = self.get_torrent(torrent_id)?;
+        if torrent.is_paused() {
+            info!("Resuming torrent");
+            self.unpause_checked(&torrent).await?;
+        }
+        Ok(())
+    }
+
+    /// Unpauses a torrent if what it has left to download fits on the volume, else reports
+    /// `DiskSpaceLow`. All torrents start through here.
+    async fn unpause_checked(&self, torrent: &Arc<ManagedTorrent>) -> KisaraResult<()> {
+        let stats = torrent.stats();
+        if !stats.finished {
+            let required = stats.total_bytes.saturating_sub(stats.progress_bytes);
+            let available = self.available_space()?;
+            if required > available {
+                let info_hash = torrent.info_hash().as_string();
+                warn!(info_hash, required, available, "Not enough disk space");
+                if let Some(app) = self.app.as_ref() {
+                    DiskSpaceLow::new(info_hash, required, available).emit(app)?;
+                }
+                return Err(KisaraError::InsufficientDiskSpace {
+                    required,
+                    available,
+                });
+            }
+        }
+        self.session.unpause(torrent).await?;
+        Ok(())
+    }
+
     pub async fn remove_torrent(&self, torrent_id: &str) -> KisaraResult<()> {
-        let torrent_id: usize = torrent_id.parse()?;
+        let torrent = self.get_torrent(torrent_id)?;
         self.session
-            .delete(TorrentIdOrHash::Id(torrent_id), true)
+            .delete(TorrentIdOrHash::Id(torrent.id()), true)
             .await?;
 
         Ok(())
     }
 
-    pub fn torrent_exists(&self, torrent_id: &str) -> KisaraResult<bool> {
-        let torrent_id: usize = torrent_id.parse()?;
-        let exists = self.session.get(TorrentIdOrHash::Id(torrent_id)).is_some();
-        Ok(exists)
+    pub fn torrent_exists(&self, torrent_id: &str) -> bool {
+        self.get_torrent(torrent_id).is_ok()
+    }
+}
+
+/// Awaits `future` unless `session` stops first, as its torrents make no progress after.
+/// Whoever opens the next session waits for them again.
+async fn until_stopped<T>(session: &Session, future: impl Future<Output = T>) -> Option<T> {
+    match select(pin!(future), pin!(session.cancellation_token().cancelled())).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
+
+/// What was last reported of each torrent, so only changes are sent.
+#[derive(Default)]
+struct ProgressReporter {
+    reported: HashMap<String, TorrentProgressEntry>,
+    /// Anime name and episode by torrent, `None` for torrents not linked to any.
+    episodes: HashMap<String, Option<(String, i32)>>,
+    /// See [`DatabaseHelper::torrent_links`](super::db::DatabaseHelper::torrent_links).
+    torrent_links: Option<Arc<AtomicU64>>,
+    /// The links `episodes` were looked up at.
+    links_seen: u64,
+    tooltip: String,
+}
+
+impl ProgressReporter {
+    async fn report(&mut self, app: &AppHandle, session: &Session) -> KisaraResult<()> {
+        let torrents = RefCell::new(Vec::new());
+        session.with_torrents(|t| {
+            torrents.replace(
+                t.map(|entry| (entry.1.info_hash().as_string(), entry.1.stats()))
+                    .collect::<Vec<_>>(),
+            );
+        });
+        let torrents = torrents.into_inner();
+        self.forget_stale_episodes(app).await;
+
+        let mut changed = Vec::new();
+        for torrent in &torrents {
+            let id = &torrent.0;
+            if !self.episodes.contains_key(id) {
+                let episode = Self::find_episode(app, id).await?;
+                self.episodes.insert(id.clone(), episode);
+            }
+            let mut entry = Self::entry(id, &torrent.1);
+            self.fill_episode(&mut entry);
+            if self.reported.get(id) == Some(&entry) {
+                continue;
+            }
+
+            let was_error = self
+                .reported
+                .get(id)
+                .is_some_and(|reported| reported.state == TorrentState::Error);
+            if entry.state == TorrentState::Error && !was_error {
+                let error = entry.error.clone().unwrap_or_default();
+                warn!(torrent_id = id, error, "Torrent failed");
+                TorrentError::new(id.clone(), error).emit(app)?;
+            }
+            self.reported.insert(id.clone(), entry.clone());
+            changed.push(entry);
+        }
+
+        let removed = self
+            .reported
+            .keys()
+            .filter(|id| !torrents.iter().any(|torrent| &torrent.0 == *id))
+            .cloned()
+            .collect::<Vec<_>>();
+        for id in &removed {
+            info!(torrent_id = id, "Torrent removed");
+            self.reported.remove(id);
+            self.episodes.remove(id);
+            TorrentRemoved::new(id.clone()).emit(app)?;
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        let downloading = torrents.iter().filter(|t| !t.1.finished).count() as u32;
+        if !changed.is_empty() || !removed.is_empty() {
+            TorrentProgress::new(changed, downloading).emit(app)?;
+        }
+        self.update_tooltip(app, downloading)
+    }
+
+    /// Torrents are linked to episodes after being added and unlinked as they are replaced,
+    /// so what was looked up is dropped whenever the links change.
+    async fn forget_stale_episodes(&mut self, app: &AppHandle) {
+        if self.torrent_links.is_none() {
+            let db_helper = app.state::<DatabaseHelperState>();
+            self.torrent_links = Some(db_helper.lock().await.torrent_links());
+        }
+        let links = self
+            .torrent_links
+            .as_ref()
+            .map_or(0, |links| links.load(Ordering::Relaxed));
+        if links != self.links_seen {
+            self.episodes.clear();
+            self.links_seen = links;
+        }
+    }
+
+    async fn find_episode(app: &AppHandle, id: &str) -> KisaraResult<Option<(String, i32)>> {
+        let db_helper = app.state::<DatabaseHelperState>();
+        let db_helper = db_helper.lock().await;
+        if let Ok(ep) = db_helper.get_ep_with_torrent_id(id.to_owned()).await {
+            let anime = db_helper.get_anime_with_ep_id(ep.id).await?;
+            return Ok(Some((anime.name_cn, ep.ep.unwrap_or(ep.sort))));
+        }
+        // replacements belong to their episode before they are linked to it
+        let Some(upgrade) = db_helper
+            .get_torrent_upgrades()
+            .await?
+            .into_iter()
+            .find(|u| u.torrent_id == id)
+        else {
+            return Ok(None);
+        };
+        let (anime, ep) = db_helper.get_anime_and_ep_with_ep_id(upgrade.ep_id).await?;
+        drop(db_helper);
+        Ok(Some((anime.name_cn, ep.ep.unwrap_or(ep.sort))))
     }
+
+    fn fill_episode(&self, entry: &mut TorrentProgressEntry) {
+        if let Some(episode) = self.episodes.get(&entry.id).and_then(Option::as_ref) {
+            entry.anime_name = Some(episode.0.clone());
+            entry.ep = Some(episode.1);
+        }
+    }
+
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn entry(id: &str, stats: &TorrentStats) -> TorrentProgressEntry {
+        let live = stats.live.as_ref();
+        let download_speed = live.map_or(0.0, |live| live.download_speed.mbps);
+        let remaining = stats.total_bytes.saturating_sub(stats.progress_bytes);
+        TorrentProgressEntry {
+            id: id.to_owned(),
+            anime_name: None,
+            ep: None,
+            state: match stats.state {
+                TorrentStatsState::Initializing => TorrentState::Initializing,
+                TorrentStatsState::Live => TorrentState::Live,
+                TorrentStatsState::Paused => TorrentState::Paused,
+                TorrentStatsState::Error => TorrentState::Error,
+            },
+            error: stats.error.clone(),
+            finished: stats.finished,
+            progress_bytes: stats.progress_bytes,
+            uploaded_bytes: stats.uploaded_bytes,
+            total_bytes: stats.total_bytes,
+            download_speed,
+            upload_speed: live.map_or(0.0, |live| live.upload_speed.mbps),
+            eta_secs: (!stats.finished && download_speed > 0.0)
+                .then(|| (remaining as f64 / (download_speed * 1024.0 * 1024.0)) as u64),
+        }
+    }
+
+    fn update_tooltip(&mut self, app: &AppHandle, downloading: u32) -> KisaraResult<()> {
+        let speed = self
+            .reported
+            .values()
+            .map(|entry| entry.download_speed)
+            .sum::<f64>();
+        let tooltip = if downloading == 0 {
+            "Kisara".to_owned()
+        } else {
+            format!("Kisara - {downloading} downloading, {speed:.2} MiB/s")
+        };
+        if tooltip != self.tooltip {
+            if let Some(tray) = app.tray_by_id(TRAY_ID) {
+                tray.set_tooltip(Some(&tooltip))?;
+            }
+            self.tooltip = tooltip;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+pub struct TorrentFile {
+    pub index: usize,
+    pub name: String,
+    pub size: u64,
+    pub selected: bool,
 }
 
 #[derive(Serialize)]
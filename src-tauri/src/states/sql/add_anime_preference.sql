@@ -0,0 +1,10 @@
+-- per-anime release preferences applied to every search, subscriptions included
+CREATE TABLE IF NOT EXISTS anime_preference (
+    anime_id INTEGER PRIMARY KEY,
+    release_group TEXT,
+    resolution INTEGER,
+    -- the kind of release, spelled like the release title parser does
+    source TEXT CHECK (source IN ('BD', 'WEB', 'TV', 'DVD')),
+    lock_group INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (anime_id) REFERENCES anime(id)
+);
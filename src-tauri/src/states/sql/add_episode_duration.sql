@@ -0,0 +1,6 @@
+-- length of each episode's video in seconds, reported by the player
+CREATE TABLE IF NOT EXISTS episode_duration (
+    ep_id INTEGER PRIMARY KEY,
+    duration INTEGER NOT NULL,
+    FOREIGN KEY (ep_id) REFERENCES episode(id)
+);
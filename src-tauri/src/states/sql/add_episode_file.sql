@@ -0,0 +1,7 @@
+-- which file of a batch torrent holds each episode
+CREATE TABLE IF NOT EXISTS episode_file (
+    ep_id INTEGER PRIMARY KEY,
+    torrent_id TEXT NOT NULL,
+    file_index INTEGER NOT NULL,
+    FOREIGN KEY (ep_id) REFERENCES episode(id)
+);
@@ -0,0 +1,15 @@
+-- seeding progress of finished torrents, the uploads librqbit reports reset on restart
+CREATE TABLE IF NOT EXISTS torrent_seeding (
+    info_hash TEXT PRIMARY KEY,
+    since TEXT NOT NULL,
+    uploaded_bytes INTEGER NOT NULL DEFAULT 0,
+    stopped INTEGER NOT NULL DEFAULT 0
+);
+
+-- seeding policies overriding the global one
+CREATE TABLE IF NOT EXISTS anime_seeding (
+    anime_id INTEGER PRIMARY KEY,
+    max_ratio REAL,
+    max_hours INTEGER,
+    FOREIGN KEY (anime_id) REFERENCES anime(id)
+);
@@ -0,0 +1,7 @@
+-- animes marked for automatic download, releases are picked by the anime's preference
+CREATE TABLE IF NOT EXISTS subscription (
+    anime_id INTEGER PRIMARY KEY,
+    delay_hours INTEGER NOT NULL,
+    retry_hours INTEGER NOT NULL,
+    FOREIGN KEY (anime_id) REFERENCES anime(id)
+);
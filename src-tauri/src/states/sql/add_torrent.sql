@@ -0,0 +1,6 @@
+-- torrents by info hash, to add them again if the session loses them
+CREATE TABLE IF NOT EXISTS torrent (
+    info_hash TEXT PRIMARY KEY,
+    magnet TEXT NOT NULL,
+    torrent_bytes BLOB
+);
@@ -0,0 +1,6 @@
+-- download priority and user pauses, which the queue respects
+CREATE TABLE IF NOT EXISTS torrent_control (
+    info_hash TEXT PRIMARY KEY,
+    priority INTEGER NOT NULL DEFAULT 0,
+    paused INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,7 @@
+-- torrents replacing the current torrent of an episode once they complete
+CREATE TABLE IF NOT EXISTS torrent_upgrade (
+    ep_id INTEGER PRIMARY KEY,
+    torrent_id TEXT NOT NULL,
+    delete_old INTEGER NOT NULL,
+    FOREIGN KEY (ep_id) REFERENCES episode(id)
+);
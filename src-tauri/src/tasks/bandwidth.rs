@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use chrono::Local;
+use tauri::{AppHandle, Manager, async_runtime::spawn};
+use tracing::{debug, instrument, warn};
+
+use crate::{
+    error::KisaraResult,
+    states::{ConfigState, QbitClientState},
+    tasks::{queue, stream::StreamServer},
+};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Follows the bandwidth schedule as time passes.
+pub fn start(app: AppHandle) {
+    spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = apply_limits(&app).await {
+                warn!(error = %e, "Failed to apply bandwidth limits");
+            }
+        }
+    });
+}
+
+/// Applies the limits configured for the current time, restarting the session if they
+/// changed. While a file is streamed the limits wait, as restarting would cut it off.
+#[instrument(level = "debug", skip(app))]
+pub async fn apply_limits(app: &AppHandle) -> KisaraResult<()> {
+    if app
+        .try_state::<StreamServer>()
+        .is_some_and(|server| server.is_streaming())
+    {
+        debug!("Streaming, leaving bandwidth limits for later");
+        return Ok(());
+    }
+    let limits = app
+        .state::<ConfigState>()
+        .lock()
+        .await
+        .download_config
+        .limits_at(Local::now().naive_local());
+    let qbit_client = app.state::<QbitClientState>();
+    let mut qbit_client = qbit_client.lock().await;
+    // the session reopens with the old limits once the downloads moved, a later check
+    // applies the new ones
+    if qbit_client.is_relocating() {
+        debug!("Downloads are moving, leaving bandwidth limits for later");
+        return Ok(());
+    }
+    let restarted = qbit_client.set_limits(limits).await?;
+    drop(qbit_client);
+    if restarted {
+        // torrents come back in whatever state they were persisted in
+        queue::rebalance(app).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{Local, NaiveDateTime};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, async_runtime::spawn};
+use tracing::{info, instrument, warn};
+
+use crate::{
+    error::KisaraResult,
+    states::{ConfigState, DatabaseHelperState, QbitClientState},
+};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CleanupReason {
+    /// Watched and past the grace period.
+    Watched,
+    /// Watched, and deleted early to get the downloads under the quota.
+    Quota,
+}
+
+/// A torrent the retention policy deletes.
+#[derive(Serialize, Debug, Clone)]
+pub struct CleanupCandidate {
+    pub torrent_id: String,
+    pub anime_name: String,
+    pub eps: Vec<i32>,
+    pub size_bytes: u64,
+    pub reason: CleanupReason,
+}
+
+/// The episodes of one torrent, as far as the retention policy is concerned.
+struct TorrentEpisodes {
+    anime_name: String,
+    eps: Vec<i32>,
+    watched: bool,
+    past_grace: bool,
+    last_watched: Option<NaiveDateTime>,
+}
+
+pub fn start(app: AppHandle) {
+    spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = clean_up(&app).await {
+                warn!(error = %e, "Failed to clean up watched episodes");
+            }
+        }
+    });
+}
+
+#[instrument(level = "info", skip(app))]
+async fn clean_up(app: &AppHandle) -> KisaraResult<()> {
+    let enabled = app
+        .state::<ConfigState>()
+        .lock()
+        .await
+        .download_config
+        .retention_policy
+        .enabled;
+    if !enabled {
+        return Ok(());
+    }
+
+    for candidate in plan(app).await? {
+        info!(?candidate, "Deleting torrent of watched episodes");
+        app.state::<QbitClientState>()
+            .lock()
+            .await
+            .remove_torrent(&candidate.torrent_id)
+            .await?;
+        app.state::<DatabaseHelperState>()
+            .lock()
+            .await
+            .remove_torrent(candidate.torrent_id)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Works out which torrents the retention policy would delete right now, whether or not it
+/// is enabled, without deleting anything.
+#[instrument(level = "info", skip(app))]
+pub async fn plan(app: &AppHandle) -> KisaraResult<Vec<CleanupCandidate>> {
+    let policy = app
+        .state::<ConfigState>()
+        .lock()
+        .await
+        .download_config
+        .retention_policy;
+    let now = Local::now().naive_local();
+    let grace = chrono::Duration::hours(policy.grace_hours.into());
+
+    let sizes = app
+        .state::<QbitClientState>()
+        .lock()
+        .await
+        .get_torrent_stats()
+        .into_iter()
+        .map(|torrent| (torrent.0, torrent.1.stats.progress_bytes))
+        .collect::<HashMap<_, _>>();
+    let episodes = app
+        .state::<DatabaseHelperState>()
+        .lock()
+        .await
+        .get_episodes_with_torrent()
+        .await?;
+
+    let mut torrents = HashMap::<String, TorrentEpisodes>::new();
+    for (anime, ep, duration) in episodes {
+        let Some(torrent_id) = ep.torrent_id else {
+            continue;
+        };
+        let watched = duration.is_some_and(|duration| {
+            i64::from(ep.progress) * 100 >= i64::from(duration) * i64::from(policy.watched_percent)
+        });
+        let past_grace = ep.last_watch_time.is_some_and(|t| now - t >= grace);
+        let entry = torrents
+            .entry(torrent_id)
+            .or_insert_with(|| TorrentEpisodes {
+                anime_name: anime.name_cn.clone(),
+                eps: Vec::new(),
+                watched: true,
+                past_grace: true,
+                last_watched: None,
+            });
+        entry.eps.push(ep.ep.unwrap_or(ep.sort));
+        entry.watched &= watched && ep.last_watch_time.is_some();
+        entry.past_grace &= past_grace;
+        entry.last_watched = entry.last_watched.max(ep.last_watch_time);
+    }
+    // torrents the session no longer has take no space and cannot be deleted
+    torrents.retain(|id, torrent| torrent.watched && sizes.contains_key(id));
+
+    let (mut expired, mut kept) = torrents
+        .into_iter()
+        .partition::<Vec<_>, _>(|torrent| torrent.1.past_grace);
+    expired.sort_by_key(|torrent| torrent.1.last_watched);
+    let mut candidates = expired
+        .into_iter()
+        .map(|(id, torrent)| candidate(id, torrent, &sizes, CleanupReason::Watched))
+        .collect::<Vec<_>>();
+
+    if let Some(quota) = policy.quota_gib {
+        let quota = u64::from(quota) * GIB;
+        let mut used =
+            sizes.values().sum::<u64>() - candidates.iter().map(|c| c.size_bytes).sum::<u64>();
+        kept.sort_by_key(|torrent| torrent.1.last_watched);
+        for (id, torrent) in kept {
+            if used <= quota {
+                break;
+            }
+            let candidate = candidate(id, torrent, &sizes, CleanupReason::Quota);
+            used -= candidate.size_bytes;
+            candidates.push(candidate);
+        }
+    }
+
+    info!(count = candidates.len(), "Planned cleanup");
+    Ok(candidates)
+}
+
+fn candidate(
+    torrent_id: String,
+    mut torrent: TorrentEpisodes,
+    sizes: &HashMap<String, u64>,
+    reason: CleanupReason,
+) -> CleanupCandidate {
+    torrent.eps.sort_unstable();
+    CleanupCandidate {
+        size_bytes: sizes.get(&torrent_id).copied().unwrap_or_default(),
+        torrent_id,
+        anime_name: torrent.anime_name,
+        eps: torrent.eps,
+        reason,
+    }
+}
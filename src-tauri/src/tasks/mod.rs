@@ -0,0 +1,20 @@
+use tauri::AppHandle;
+
+pub mod bandwidth;
+pub mod cleanup;
+pub mod queue;
+mod seeding;
+pub mod stream;
+mod subscription;
+pub mod upgrade;
+
+/// Starts the background tasks that run for the lifetime of the app.
+pub fn start(app: &AppHandle) {
+    bandwidth::start(app.clone());
+    cleanup::start(app.clone());
+    queue::start(app);
+    seeding::start(app.clone());
+    stream::start(app.clone());
+    subscription::start(app.clone());
+    upgrade::start(app.clone());
+}
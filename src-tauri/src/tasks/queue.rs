@@ -0,0 +1,128 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use tauri::{AppHandle, Listener, Manager, async_runtime::spawn};
+use tracing::{info, instrument, warn};
+
+use crate::{
+    error::KisaraResult,
+    events::{DiskSpaceLow, Event, TorrentPaused, TorrentResumed},
+    states::{ConfigState, DatabaseHelperState, QbitClientState},
+};
+
+const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Keeps the queue in order as torrents are added and complete, and as the download
+/// volume fills up.
+pub fn start(app: &AppHandle) {
+    for event in ["torrent-init", "torrent-complete"] {
+        let handle = app.clone();
+        app.listen(event, move |_| spawn_rebalance(&handle));
+    }
+    let app = app.clone();
+    spawn(async move {
+        let mut interval = tokio::time::interval(DISK_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = rebalance(&app).await {
+                warn!(error = %e, "Failed to rebalance download queue");
+            }
+        }
+    });
+}
+
+fn spawn_rebalance(app: &AppHandle) {
+    let app = app.clone();
+    spawn(async move {
+        if let Err(e) = rebalance(&app).await {
+            warn!(error = %e, "Failed to rebalance download queue");
+        }
+    });
+}
+
+/// Lets the highest priority torrents download, up to the configured number and as long as
+/// what they have left fits on the download volume, and pauses the others. Torrents of
+/// equal priority download in the order they were added.
+#[instrument(level = "info", skip(app))]
+pub async fn rebalance(app: &AppHandle) -> KisaraResult<()> {
+    let max_active = app
+        .state::<ConfigState>()
+        .lock()
+        .await
+        .download_config
+        .max_active_downloads;
+    let controls = app
+        .state::<DatabaseHelperState>()
+        .lock()
+        .await
+        .get_torrent_controls()
+        .await?
+        .into_iter()
+        .map(|c| (c.info_hash.clone(), c))
+        .collect::<HashMap<_, _>>();
+
+    let qbit_client = app.state::<QbitClientState>();
+    let qbit_client = qbit_client.lock().await;
+    let torrents = qbit_client.get_unfinished_torrents();
+
+    let mut waiting = torrents
+        .iter()
+        .filter(|t| !controls.get(&t.0).is_some_and(|c| c.paused))
+        .map(|t| t.0.as_str())
+        .collect::<Vec<_>>();
+    // stable, so equal priorities keep their order
+    waiting.sort_by_key(|id| Reverse(controls.get(*id).map_or(0, |c| c.priority)));
+    if max_active > 0 {
+        waiting.truncate(max_active as usize);
+    }
+    // what running torrents have left counts against the space too
+    let mut available = qbit_client.available_space()?;
+    let mut no_space = HashSet::new();
+    waiting.retain(|id| {
+        let remaining = qbit_client.remaining_bytes(id).unwrap_or_default();
+        if remaining > available {
+            no_space.insert(*id);
+            return false;
+        }
+        available -= remaining;
+        true
+    });
+    info!(
+        active = waiting.len(),
+        total = torrents.len(),
+        "Rebalancing"
+    );
+
+    for torrent in &torrents {
+        let (id, paused) = (&torrent.0, torrent.1);
+        let should_run = waiting.contains(&id.as_str());
+        // one torrent failing should not leave the rest of the queue unbalanced
+        if should_run && paused {
+            if let Err(e) = qbit_client.resume_torrent(id).await {
+                warn!(torrent_id = id, error = %e, "Failed to resume queued torrent");
+                continue;
+            }
+            TorrentResumed::new(id.clone()).emit(app)?;
+        } else if !should_run && !paused {
+            if let Err(e) = qbit_client.pause_torrent(id).await {
+                warn!(torrent_id = id, error = %e, "Failed to pause queued torrent");
+                continue;
+            }
+            let queued = !controls.get(id).is_some_and(|c| c.paused);
+            TorrentPaused::new(id.clone(), queued).emit(app)?;
+            if no_space.contains(id.as_str()) {
+                let remaining = qbit_client.remaining_bytes(id)?;
+                warn!(
+                    torrent_id = id,
+                    remaining, available, "Paused for disk space"
+                );
+                DiskSpaceLow::new(id.clone(), remaining, available).emit(app)?;
+            }
+        }
+    }
+    drop(qbit_client);
+    Ok(())
+}
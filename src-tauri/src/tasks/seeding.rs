@@ -0,0 +1,117 @@
+use std::{collections::HashMap, time::Duration};
+
+use chrono::Utc;
+use tauri::{AppHandle, Manager, async_runtime::spawn};
+use tracing::{info, instrument, warn};
+
+use crate::{
+    data::seeding::TorrentSeeding,
+    error::KisaraResult,
+    events::{Event, SeedingStopped},
+    states::{ConfigState, DatabaseHelperState, QbitClientState},
+};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Stops finished torrents once they reach their seeding policy.
+pub fn start(app: AppHandle) {
+    spawn(async move {
+        // what librqbit reported uploaded for each torrent on the last check
+        let mut counters = HashMap::new();
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = check_seeding(&app, &mut counters).await {
+                warn!(error = %e, "Failed to check seeding torrents");
+            }
+        }
+    });
+}
+
+#[instrument(level = "debug", skip(app, counters))]
+async fn check_seeding(app: &AppHandle, counters: &mut HashMap<String, u64>) -> KisaraResult<()> {
+    let now = Utc::now();
+    let default_policy = app
+        .state::<ConfigState>()
+        .lock()
+        .await
+        .download_config
+        .seeding_policy;
+    let finished = app
+        .state::<QbitClientState>()
+        .lock()
+        .await
+        .get_torrent_stats()
+        .into_iter()
+        .filter(|torrent| torrent.1.stats.finished)
+        .collect::<Vec<_>>();
+
+    let db_helper = app.state::<DatabaseHelperState>();
+    let db_helper = db_helper.lock().await;
+    let mut seedings = db_helper
+        .get_torrent_seedings()
+        .await?
+        .into_iter()
+        .map(|s| (s.info_hash.clone(), s))
+        .collect::<HashMap<_, _>>();
+    let mut updated = Vec::new();
+    for (id, torrent) in finished {
+        let known = seedings.remove(&id);
+        let is_new = known.is_none();
+        let mut seeding = known.unwrap_or_else(|| TorrentSeeding::new(id.clone(), now));
+
+        // the counter starts over whenever the torrent does
+        let counter = torrent.stats.uploaded_bytes;
+        let last = counters.insert(id.clone(), counter).unwrap_or_default();
+        let uploaded = counter.checked_sub(last).unwrap_or(counter);
+        seeding.uploaded_bytes += uploaded;
+
+        // once stopped, a torrent the user resumes seeds for as long as they like
+        let stop = if !seeding.stopped && torrent.stats.live.is_some() {
+            let anime_policy = match db_helper.get_ep_with_torrent_id(id.clone()).await {
+                Ok(ep) => db_helper.get_anime_seeding_policy(ep.anime_id).await?,
+                Err(_) => None,
+            };
+            anime_policy.unwrap_or(default_policy).is_reached(
+                &seeding,
+                torrent.stats.total_bytes,
+                now,
+            )
+        } else {
+            false
+        };
+        if is_new || uploaded > 0 || stop {
+            updated.push((seeding, stop));
+        }
+    }
+    drop(db_helper);
+
+    for entry in &mut updated {
+        if !entry.1 {
+            continue;
+        }
+        let id = entry.0.info_hash.clone();
+        info!(id, "Seeding policy reached, stopping torrent");
+        let paused = app
+            .state::<QbitClientState>()
+            .lock()
+            .await
+            .pause_torrent(&id)
+            .await;
+        match paused {
+            Ok(()) => {
+                entry.0.stopped = true;
+                SeedingStopped::new(id).emit(app)?;
+            }
+            Err(e) => warn!(error = %e, id, "Failed to stop seeding"),
+        }
+    }
+
+    let db_helper = app.state::<DatabaseHelperState>();
+    let db_helper = db_helper.lock().await;
+    for (seeding, _) in updated {
+        db_helper.save_torrent_seeding(seeding).await?;
+    }
+    drop(db_helper);
+    Ok(())
+}
@@ -0,0 +1,336 @@
+use std::{
+    io::SeekFrom,
+    net::Ipv4Addr,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use tauri::{AppHandle, Manager, async_runtime::spawn};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{error::KisaraResult, states::QbitClientState};
+
+/// Longest request line and headers accepted together, players send far shorter ones.
+const MAX_HEAD_LEN: u64 = 16 * 1024;
+
+/// Origins of the app's webview, the only page allowed to read streams from scripts.
+const APP_ORIGINS: [&str; 3] = [
+    "tauri://localhost",
+    "http://tauri.localhost",
+    "https://tauri.localhost",
+];
+
+/// The local HTTP server streaming the files of torrents as they download.
+pub struct StreamServer {
+    port: u16,
+    /// Random for each run and part of every URL, so other programs and web pages cannot
+    /// guess their way to the files.
+    token: String,
+    /// Number of files being streamed right now.
+    active: Arc<AtomicUsize>,
+}
+
+impl StreamServer {
+    /// Whether a player is reading a file, which restarting the session would cut off.
+    pub fn is_streaming(&self) -> bool {
+        self.active.load(Ordering::Relaxed) > 0
+    }
+
+    /// The URL a player can read a file of a torrent from, with range requests.
+    pub fn url(&self, torrent_id: &str, file: usize) -> String {
+        format!(
+            "http://127.0.0.1:{}/{}/torrents/{torrent_id}/{file}",
+            self.port, self.token
+        )
+    }
+}
+
+/// Counts a stream as active for as long as it is held.
+struct ActiveStream(Arc<AtomicUsize>);
+
+impl ActiveStream {
+    fn new(active: &Arc<AtomicUsize>) -> Self {
+        active.fetch_add(1, Ordering::Relaxed);
+        Self(Arc::clone(active))
+    }
+}
+
+impl Drop for ActiveStream {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// A request for a file of a torrent, the `Range` header is kept as is.
+struct StreamRequest {
+    head: bool,
+    token: String,
+    torrent_id: String,
+    file: usize,
+    range: Option<String>,
+    origin: Option<String>,
+}
+
+/// Binds the server to a free port on localhost and manages its `StreamServer`. Streaming
+/// is unavailable if binding fails.
+pub fn start(app: AppHandle) {
+    let listener = match std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+    {
+        Ok(listener) => listener,
+        Err(e) => {
+            warn!(error = %e, "Failed to bind stream server");
+            return;
+        }
+    };
+    let port = match listener.local_addr() {
+        Ok(addr) => addr.port(),
+        Err(e) => {
+            warn!(error = %e, "Failed to get stream server address");
+            return;
+        }
+    };
+    info!(port, "Stream server listening");
+    app.manage(StreamServer {
+        port,
+        token: Uuid::new_v4().simple().to_string(),
+        active: Arc::new(AtomicUsize::new(0)),
+    });
+
+    spawn(async move {
+        let listener = match TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!(error = %e, "Failed to start stream server");
+                return;
+            }
+        };
+        loop {
+            match listener.accept().await {
+                Ok((socket, _)) => {
+                    let app = app.clone();
+                    spawn(async move {
+                        if let Err(e) = serve(&app, socket).await {
+                            warn!(error = %e, "Failed to serve stream request");
+                        }
+                    });
+                }
+                Err(e) => warn!(error = %e, "Failed to accept stream connection"),
+            }
+        }
+    });
+}
+
+#[instrument(level = "debug", skip(app, socket))]
+async fn serve(app: &AppHandle, socket: TcpStream) -> KisaraResult<()> {
+    let mut socket = BufReader::new(socket);
+    let Some(request) = read_request(&mut socket).await? else {
+        return respond_status(socket.get_mut(), "400 Bad Request").await;
+    };
+    let server = app.state::<StreamServer>();
+    if request.token != server.token {
+        return respond_status(socket.get_mut(), "403 Forbidden").await;
+    }
+
+    let opened = {
+        let qbit = app.state::<QbitClientState>();
+        let qbit = qbit.lock().await;
+        qbit.get_file_path(&request.torrent_id, request.file)
+            .and_then(|path| {
+                qbit.open_stream(&request.torrent_id, request.file)
+                    .map(|stream| (path, stream))
+            })
+    };
+    let (path, (mut stream, len)) = match opened {
+        Ok(opened) => opened,
+        Err(e) => {
+            warn!(error = %e, "Failed to open stream");
+            return respond_status(socket.get_mut(), "404 Not Found").await;
+        }
+    };
+    let _active = ActiveStream::new(&server.active);
+
+    let range = request
+        .range
+        .as_deref()
+        .map(|range| parse_range(range, len));
+    if range == Some(None) {
+        let headers = format!(
+            "HTTP/1.1 416 Range Not Satisfiable\r\n\
+            Content-Range: bytes */{len}\r\n\
+            Content-Length: 0\r\n\
+            Connection: close\r\n\r\n"
+        );
+        socket.get_mut().write_all(headers.as_bytes()).await?;
+        return Ok(());
+    }
+    let range = range.flatten();
+    let (start, end) = range.unwrap_or_else(|| (0, len.saturating_sub(1)));
+    let length = if len == 0 { 0 } else { end - start + 1 };
+    info!(?path, start, end, len, "Streaming file");
+
+    let status = if range.is_some() {
+        format!("206 Partial Content\r\nContent-Range: bytes {start}-{end}/{len}")
+    } else {
+        "200 OK".to_owned()
+    };
+    // the player loads the video with CORS, which only the app itself gets past
+    let allow_origin = request
+        .origin
+        .filter(|origin| is_app_origin(app, origin))
+        .map(|origin| format!("Access-Control-Allow-Origin: {origin}\r\nVary: Origin\r\n"))
+        .unwrap_or_default();
+    let headers = format!(
+        "HTTP/1.1 {status}\r\n\
+        Content-Type: {}\r\n\
+        Content-Length: {length}\r\n\
+        Accept-Ranges: bytes\r\n\
+        {allow_origin}\
+        Connection: close\r\n\r\n",
+        content_type(Path::new(&path))
+    );
+    let socket = socket.get_mut();
+    socket.write_all(headers.as_bytes()).await?;
+    if request.head || length == 0 {
+        return Ok(());
+    }
+
+    stream.seek(SeekFrom::Start(start)).await?;
+    // players drop connections all the time when seeking, which is not worth reporting
+    if let Err(e) = tokio::io::copy(&mut stream.take(length), socket).await {
+        info!(error = %e, "Stream closed early");
+    }
+    Ok(())
+}
+
+/// Reads the request line and headers, `None` if they are not a request for a file or
+/// longer than `MAX_HEAD_LEN`.
+async fn read_request(socket: &mut BufReader<TcpStream>) -> KisaraResult<Option<StreamRequest>> {
+    let mut lines = socket.take(MAX_HEAD_LEN);
+    let mut line = String::new();
+    // a line without its end ran out of the limit, or the connection closed
+    lines.read_line(&mut line).await?;
+    if !line.ends_with('\n') {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let head = match parts.next() {
+        Some("GET") => false,
+        Some("HEAD") => true,
+        _ => return Ok(None),
+    };
+    let target = parts.next().unwrap_or_default().to_owned();
+
+    let mut range = None;
+    let mut origin = None;
+    loop {
+        line.clear();
+        lines.read_line(&mut line).await?;
+        if !line.ends_with('\n') {
+            return Ok(None);
+        }
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let name = name.trim();
+            if name.eq_ignore_ascii_case("range") {
+                range = Some(value.trim().to_owned());
+            } else if name.eq_ignore_ascii_case("origin") {
+                origin = Some(value.trim().to_owned());
+            }
+        }
+    }
+
+    let mut segments = target
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches('/')
+        .split('/');
+    let (Some(token), Some("torrents"), Some(torrent_id), Some(file), None) = (
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+    ) else {
+        return Ok(None);
+    };
+    let Ok(file) = file.parse() else {
+        return Ok(None);
+    };
+    Ok(Some(StreamRequest {
+        head,
+        token: token.to_owned(),
+        torrent_id: torrent_id.to_owned(),
+        file,
+        range,
+        origin,
+    }))
+}
+
+/// Whether `origin` is the app's webview, or the dev server serving it in debug builds.
+fn is_app_origin(app: &AppHandle, origin: &str) -> bool {
+    APP_ORIGINS.contains(&origin)
+        || (cfg!(debug_assertions)
+            && app
+                .config()
+                .build
+                .dev_url
+                .as_ref()
+                .is_some_and(|url| url.origin().ascii_serialization() == origin))
+}
+
+/// Parses a single `bytes=` range into the first and last byte, both inclusive. Multiple
+/// ranges are not supported and yield `None` like unsatisfiable ones.
+fn parse_range(range: &str, len: u64) -> Option<(u64, u64)> {
+    let (start, end) = range.strip_prefix("bytes=")?.trim().split_once('-')?;
+    let (start, end) = if start.is_empty() {
+        // the last `end` bytes
+        let suffix = end.parse::<u64>().ok()?.min(len);
+        (len.checked_sub(suffix)?, len.checked_sub(1)?)
+    } else {
+        let start = start.parse::<u64>().ok()?;
+        let end = if end.is_empty() {
+            len.checked_sub(1)?
+        } else {
+            end.parse::<u64>().ok()?.min(len.checked_sub(1)?)
+        };
+        (start, end)
+    };
+    (start <= end && end < len).then_some((start, end))
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    match ext.as_str() {
+        "mkv" => "video/x-matroska",
+        "mp4" | "m4v" => "video/mp4",
+        "webm" => "video/webm",
+        "avi" => "video/x-msvideo",
+        "ts" => "video/mp2t",
+        "ass" | "ssa" => "text/x-ssa",
+        "srt" => "application/x-subrip",
+        "vtt" => "text/vtt",
+        _ => "application/octet-stream",
+    }
+}
+
+async fn respond_status(socket: &mut TcpStream, status: &str) -> KisaraResult<()> {
+    let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    socket.write_all(response.as_bytes()).await?;
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tauri::{AppHandle, Manager, async_runtime::spawn};
+use tauri_plugin_notification::NotificationExt;
+use tracing::{info, instrument, warn};
+
+use crate::{
+    data::{anime::Anime, episode::Episode, subscription::Subscription},
+    error::KisaraResult,
+    events::{Event, SubscriptionDownload},
+    states::{DatabaseHelperState, QbitClientState, TorrentAdapterRegistryState},
+};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+pub fn start(app: AppHandle) {
+    spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = check_subscriptions(&app).await {
+                warn!(error = %e, "Failed to check subscriptions");
+            }
+        }
+    });
+}
+
+#[instrument(level = "info", skip(app))]
+async fn check_subscriptions(app: &AppHandle) -> KisaraResult<()> {
+    let now = Utc::now();
+    let due = app
+        .state::<DatabaseHelperState>()
+        .lock()
+        .await
+        .get_subscribed_episodes_without_torrent()
+        .await?
+        .into_iter()
+        .filter(|due| due.2.is_due(&due.1, now))
+        .collect::<Vec<_>>();
+    info!(count = due.len(), "Checking subscribed episodes");
+
+    // one failing episode should not keep the others from downloading
+    for (anime, ep, _) in due {
+        if let Err(e) = download_episode(app, &anime, &ep).await {
+            warn!(error = %e, ep_id = ep.id, "Failed to download subscribed episode");
+        }
+    }
+    Ok(())
+}
+
+#[instrument(level = "info", skip(app, anime), fields(ep_id = ep.id))]
+async fn download_episode(app: &AppHandle, anime: &Anime, ep: &Episode) -> KisaraResult<()> {
+    let preference = app
+        .state::<DatabaseHelperState>()
+        .lock()
+        .await
+        .get_anime_preference(anime.id)
+        .await?;
+    let results = app
+        .state::<TorrentAdapterRegistryState>()
+        .lock()
+        .await
+        .init_search(ep, anime, preference.as_ref(), None, false)
+        .await;
+
+    let ep_num = ep.ep.unwrap_or(ep.sort);
+    // results are ranked, so the first acceptable one is the best
+    let Some(torrent) = results
+        .torrents
+        .into_iter()
+        .find(|t| Subscription::accepts(t, ep_num, preference.as_ref()))
+    else {
+        info!("No matching release yet");
+        return Ok(());
+    };
+
+    info!(name = torrent.name, "Downloading matching release");
+    let record = app
+        .state::<QbitClientState>()
+        .lock()
+        .await
+        .add_torrent(&torrent.magnet)
+        .await?;
+    let torrent_id = record.info_hash.clone();
+    let db_helper = app.state::<DatabaseHelperState>();
+    let db_helper = db_helper.lock().await;
+    db_helper.save_torrent(record).await?;
+    db_helper
+        .set_episode_torrent_id(ep.id, torrent_id.clone())
+        .await?;
+    if let Some(group) = torrent.group() {
+        db_helper
+            .lock_anime_group(anime.id, group.to_owned())
+            .await?;
+    }
+    drop(db_helper);
+
+    let _ = app
+        .notification()
+        .builder()
+        .title("Episode Download Started")
+        .body(format!(
+            "{} episode {}: {}",
+            anime.name_cn, ep_num, torrent.name
+        ))
+        .show();
+    SubscriptionDownload::new(anime.id, ep.id, torrent_id, torrent.name).emit(app)?;
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, async_runtime::spawn};
+use tracing::{info, instrument, warn};
+
+use crate::{
+    data::torrent_upgrade::TorrentUpgrade,
+    error::KisaraResult,
+    events::{Event, TorrentReplaced},
+    states::{DatabaseHelperState, QbitClientState},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Resumes watching the upgrades that were still downloading when the app was closed.
+pub fn start(app: AppHandle) {
+    spawn(async move {
+        let upgrades = app
+            .state::<DatabaseHelperState>()
+            .lock()
+            .await
+            .get_torrent_upgrades()
+            .await;
+        match upgrades {
+            Ok(upgrades) => upgrades.into_iter().for_each(|u| watch(&app, u)),
+            Err(e) => warn!(error = %e, "Failed to load torrent upgrades"),
+        }
+    });
+}
+
+/// Replaces the episode's torrent with the upgrade once it completes.
+pub fn watch(app: &AppHandle, upgrade: TorrentUpgrade) {
+    let app = app.clone();
+    spawn(async move {
+        if let Err(e) = wait_and_replace(&app, &upgrade).await {
+            warn!(error = %e, ep_id = upgrade.ep_id, "Failed to upgrade torrent");
+        }
+    });
+}
+
+#[instrument(level = "info", skip(app))]
+async fn wait_and_replace(app: &AppHandle, upgrade: &TorrentUpgrade) -> KisaraResult<()> {
+    // polled rather than awaited on the torrent, which goes away when the session restarts
+    while !app
+        .state::<QbitClientState>()
+        .lock()
+        .await
+        .is_finished(&upgrade.torrent_id)?
+    {
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    info!("Upgrade completed, replacing torrent");
+
+    let db_helper = app.state::<DatabaseHelperState>();
+    let db_helper = db_helper.lock().await;
+    let old_torrent_id = db_helper.get_torrent_id_with_ep_id(upgrade.ep_id).await?;
+    db_helper
+        .set_episode_torrent_id(upgrade.ep_id, upgrade.torrent_id.clone())
+        .await?;
+    db_helper.remove_torrent_upgrade(upgrade.ep_id).await?;
+    // other episodes of a batch may still play from the old torrent
+    let delete_old = match old_torrent_id {
+        Some(ref old) if upgrade.delete_old && old != &upgrade.torrent_id => {
+            !db_helper.torrent_in_use(old.clone()).await?
+        }
+        _ => false,
+    };
+    drop(db_helper);
+
+    if delete_old && let Some(ref old) = old_torrent_id {
+        info!(old, "Deleting replaced torrent");
+        app.state::<QbitClientState>()
+            .lock()
+            .await
+            .remove_torrent(old)
+            .await?;
+    }
+    TorrentReplaced::new(upgrade.ep_id, upgrade.torrent_id.clone(), old_torrent_id).emit(app)?;
+    Ok(())
+}
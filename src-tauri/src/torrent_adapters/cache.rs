@@ -0,0 +1,62 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use super::TorrentInfo;
+
+#[derive(Hash, PartialEq, Eq)]
+struct CacheKey {
+    source: String,
+    query: String,
+    page: u32,
+}
+
+/// Raw results of each source, so reopening an episode does not query every site again.
+///
+/// Entries are keyed by source, query and page and kept in memory for the configured TTL.
+/// A TTL of zero disables caching.
+pub struct SearchCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<CacheKey, (Instant, Vec<TorrentInfo>)>>,
+}
+
+impl SearchCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, source: &str, query: &str, page: u32) -> Option<Vec<TorrentInfo>> {
+        let key = CacheKey {
+            source: source.to_owned(),
+            query: query.to_owned(),
+            page,
+        };
+        self.entries
+            .lock()
+            .expect("Search cache lock poisoned")
+            .get(&key)
+            .filter(|entry| entry.0.elapsed() < self.ttl)
+            .map(|entry| entry.1.clone())
+    }
+
+    pub fn insert(&self, source: &str, query: &str, page: u32, torrents: &[TorrentInfo]) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.lock().expect("Search cache lock poisoned");
+        entries.retain(|_, entry| entry.0.elapsed() < self.ttl);
+        entries.insert(
+            CacheKey {
+                source: source.to_owned(),
+                query: query.to_owned(),
+                page,
+            },
+            (Instant::now(), torrents.to_vec()),
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use async_trait::async_trait;
+use kuchikiki::{NodeRef, traits::TendrilSink};
+use reqwest::Client;
+use tracing::{info, instrument};
+
+use crate::{
+    data::{anime::Anime, episode::Episode},
+    error::{KisaraError, KisaraResult},
+    utils::release_name::ReleaseInfo,
+};
+
+use super::{
+    TorrentAdapter, TorrentAdapterFactory, TorrentInfo,
+    fields::{CST_OFFSET_SECS, parse_date, parse_size},
+    merge_keyword_searches, padded_ep_number, release_names,
+};
+
+const DMHY_BASE_URL: &str = "https://share.dmhy.org";
+
+pub struct DmhyAdapter<'a> {
+    ep: &'a Episode,
+    anime: &'a Anime,
+    client: &'a Client,
+}
+
+impl DmhyAdapter<'_> {
+    #[instrument(level = "info", skip(self))]
+    async fn search_keyword(&self, keyword: String, page: u32) -> KisaraResult<Vec<TorrentInfo>> {
+        info!("Searching");
+        // sort_id 2 is the anime category
+        let url = format!("{}/topics/list/page/{}", DMHY_BASE_URL, page.max(1));
+        let response = self
+            .client
+            .get(&url)
+            .query(&[("keyword", keyword.as_str()), ("sort_id", "2")])
+            .send()
+            .await?
+            .text()
+            .await?;
+        Self::parse(&response)
+    }
+
+    fn cell_text(row: &NodeRef, selector: &str) -> Option<String> {
+        row.select(selector)
+            .ok()
+            .and_then(|mut node| node.next())
+            .map(|node| node.text_contents().trim().to_owned())
+            .filter(|s| !s.is_empty())
+    }
+
+    fn parse(html: &str) -> KisaraResult<Vec<TorrentInfo>> {
+        let doc = kuchikiki::parse_html().one(html);
+
+        let table = doc.select_first("table#topic_list").map_err(|_| {
+            KisaraError::HtmlParseError("Failed to select table#topic_list".to_owned())
+        })?;
+
+        let ret = table
+            .as_node()
+            .select("tbody > tr")
+            .map_err(|_| {
+                KisaraError::HtmlParseError("Failed to select rows in table#topic_list".to_owned())
+            })?
+            .filter_map(|node| {
+                let row = node.as_node();
+
+                // the title cell holds an optional team tag followed by the topic link
+                let (name, url) = row
+                    .select("td.title > a[href^=\"/topics/view/\"]")
+                    .ok()
+                    .and_then(|mut node| node.next())
+                    .map(|node| {
+                        (
+                            node.text_contents().trim().to_owned(),
+                            node.attributes
+                                .borrow()
+                                .get("href")
+                                .map(|s| format!("{}{}", DMHY_BASE_URL, s)),
+                        )
+                    })?;
+
+                let magnet = row
+                    .select("a[href^=\"magnet:\"]")
+                    .ok()
+                    .and_then(|mut node| node.next())
+                    .and_then(|node| node.attributes.borrow().get("href").map(ToOwned::to_owned))?;
+
+                // the first cell repeats its date in a hidden span, which is the cleaner copy
+                let date = Self::cell_text(row, "td:nth-child(1) > span")
+                    .map(|date| date.replace('/', "-"));
+                let timestamp = date
+                    .as_deref()
+                    .and_then(|date| parse_date(date, CST_OFFSET_SECS));
+
+                let size = Self::cell_text(row, "td:nth-child(5)");
+                let size_bytes = size.as_deref().and_then(parse_size);
+
+                let seeders =
+                    Self::cell_text(row, "td:nth-child(6)").and_then(|s| s.parse::<u32>().ok());
+
+                let leechers =
+                    Self::cell_text(row, "td:nth-child(7)").and_then(|s| s.parse::<u32>().ok());
+
+                // releases are chosen by team, so prefer it over the publishing account
+                let uploader = Self::cell_text(row, "td.title > span.tag > a")
+                    .or_else(|| Self::cell_text(row, "td:last-child > a"));
+
+                let release = ReleaseInfo::parse(&name);
+
+                Some(TorrentInfo {
+                    name,
+                    size,
+                    size_bytes,
+                    url,
+                    magnet,
+                    date,
+                    timestamp,
+                    seeders,
+                    leechers,
+                    uploader,
+                    release,
+                    source: String::new(),
+                    score: None,
+                })
+            })
+            .collect::<Vec<_>>();
+        info!("Parsed {} torrents", ret.len());
+
+        Ok(ret)
+    }
+}
+
+#[async_trait]
+impl TorrentAdapter for DmhyAdapter<'_> {
+    #[instrument(level = "info", skip(self))]
+    async fn search(&self, page: u32, query: Option<&str>) -> KisaraResult<Vec<TorrentInfo>> {
+        let keywords = query.map_or_else(
+            || {
+                let ep = padded_ep_number(self.ep);
+                release_names(self.anime)
+                    .into_iter()
+                    .map(|name| format!("{} {}", name, ep))
+                    .collect::<Vec<_>>()
+            },
+            |query| vec![query.to_owned()],
+        );
+
+        info!(?keywords);
+
+        let results = merge_keyword_searches(
+            keywords
+                .into_iter()
+                .map(|keyword| self.search_keyword(keyword, page)),
+        )
+        .await?;
+
+        info!("Found {} torrents", results.len());
+
+        Ok(results)
+    }
+}
+
+pub struct DmhyAdapterFactory;
+
+impl DmhyAdapterFactory {
+    pub const fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<'a> TorrentAdapterFactory<'a> for DmhyAdapterFactory {
+    fn source_name(&self) -> String {
+        "Dmhy".to_owned()
+    }
+
+    fn create_adapter(
+        &'a self,
+        ep: &'a Episode,
+        anime: &'a Anime,
+        client: &'a Client,
+    ) -> Box<dyn TorrentAdapter + Send + Sync + 'a> {
+        Box::new(DmhyAdapter { ep, anime, client })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use crate::error::KisaraError;
+
+    use super::DmhyAdapter;
+
+    const DMHY: &str = include_str!("fixtures/dmhy.html");
+
+    #[test]
+    fn parses_topic_list() {
+        let torrents = DmhyAdapter::parse(DMHY).expect("Failed to parse fixture");
+        // the last topic has no magnet to download
+        assert_eq!(torrents.len(), 2);
+
+        let torrent = &torrents[0];
+        assert_eq!(
+            torrent.name,
+            "[LoliHouse] 葬送的芙莉莲 / Sousou no Frieren - 05 [WebRip 1080p HEVC-10bit AAC][简繁内封字幕]"
+        );
+        assert_eq!(
+            torrent.url.as_deref(),
+            Some("https://share.dmhy.org/topics/view/652345_LoliHouse_Sousou_no_Frieren_-_05.html")
+        );
+        assert!(
+            torrent
+                .magnet
+                .starts_with("magnet:?xt=urn:btih:LMGD7DRKPVGB5H3KRM6SYHQPTGFXM3K5&dn=")
+        );
+        assert_eq!(torrent.size.as_deref(), Some("336.5MB"));
+        assert_eq!(torrent.size_bytes, Some(352_845_824));
+        assert_eq!(torrent.date.as_deref(), Some("2023-10-07 00:05"));
+        assert_eq!(
+            torrent.timestamp,
+            DateTime::parse_from_rfc3339("2023-10-06T16:05:00Z")
+                .ok()
+                .map(|date| date.to_utc())
+        );
+        assert_eq!(torrent.seeders, Some(52));
+        assert_eq!(torrent.leechers, Some(3));
+        assert_eq!(torrent.uploader.as_deref(), Some("LoliHouse"));
+
+        // without a team tag the publishing account is the uploader
+        let torrent = &torrents[1];
+        assert_eq!(torrent.name, "葬送的芙莉莲 Sousou no Frieren 05 1080p");
+        assert_eq!(torrent.seeders, None);
+        assert_eq!(torrent.leechers, None);
+        assert_eq!(torrent.uploader.as_deref(), Some("frieren_fan"));
+    }
+
+    #[test]
+    fn rejects_pages_without_topic_list() {
+        let result = DmhyAdapter::parse("<html><body><p>502 Bad Gateway</p></body></html>");
+        assert!(matches!(result, Err(KisaraError::HtmlParseError(_))));
+    }
+}
@@ -8,7 +8,7 @@ pub struct DummyAdapter;
 
 #[async_trait]
 impl TorrentAdapter for DummyAdapter {
-    async fn search(&self, _page: u32) -> KisaraResult<Vec<TorrentInfo>> {
+    async fn search(&self, _page: u32, _query: Option<&str>) -> KisaraResult<Vec<TorrentInfo>> {
         Ok(vec![])
     }
 }
@@ -27,7 +27,7 @@ impl<'a> TorrentAdapterFactory<'a> for DummyAdapterFactory {
     }
 
     fn create_adapter(
-        &self,
+        &'a self,
         _ep: &'a crate::data::episode::Episode,
         _anime: &'a crate::data::anime::Anime,
         _client: &'a reqwest::Client,
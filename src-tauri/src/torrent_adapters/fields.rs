@@ -0,0 +1,82 @@
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+
+/// Offset of China Standard Time, used by sites that show dates without one.
+pub const CST_OFFSET_SECS: i32 = 8 * 3600;
+
+/// Naive formats seen on supported sites, tried in order.
+const NAIVE_DATE_FORMATS: [&str; 5] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+];
+
+/// Human readable size in the same style nyaa displays it, e.g. `1.3 GiB`.
+#[allow(clippy::cast_precision_loss)]
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["Bytes", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Inverse of [`format_size`], also accepting the unit spellings other sites use, e.g. `1.2GB`.
+/// Sites use binary units even when they write `GB`.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+pub fn parse_size(size: &str) -> Option<u64> {
+    let size = size.trim();
+    let split = size
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(split);
+    let number = number.parse::<f64>().ok()?;
+    let exp = match unit.trim().chars().next().map(|c| c.to_ascii_lowercase()) {
+        None | Some('b') => 0,
+        Some('k') => 1,
+        Some('m') => 2,
+        Some('g') => 3,
+        Some('t') => 4,
+        Some(_) => return None,
+    };
+    Some((number * 1024_f64.powi(exp)) as u64)
+}
+
+/// Parses RFC 2822 and RFC 3339 dates as well as the naive formats sites display. Naive
+/// dates are taken to be `offset_secs` seconds east of UTC, the site's local time.
+pub fn parse_date(date: &str, offset_secs: i32) -> Option<DateTime<Utc>> {
+    let date = date.trim();
+    if let Ok(date) =
+        DateTime::parse_from_rfc2822(date).or_else(|_| DateTime::parse_from_rfc3339(date))
+    {
+        return Some(date.to_utc());
+    }
+    let offset = FixedOffset::east_opt(offset_secs)?;
+    NAIVE_DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+        .and_then(|date| date.and_local_timezone(offset).single())
+        .map(|date| date.to_utc())
+}
+
+/// Feed dates (RFC 2822 in RSS, RFC 3339 in Atom) in the same style nyaa displays them.
+pub fn format_feed_date(date: &str) -> String {
+    DateTime::parse_from_rfc2822(date)
+        .or_else(|_| DateTime::parse_from_rfc3339(date))
+        .map_or_else(
+            |_| date.to_owned(),
+            |d| d.format("%Y-%m-%d %H:%M").to_string(),
+        )
+}
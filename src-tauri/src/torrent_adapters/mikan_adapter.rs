@@ -0,0 +1,236 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::{info, instrument};
+
+use crate::{
+    data::{anime::Anime, episode::Episode},
+    error::KisaraResult,
+    utils::release_name::ReleaseInfo,
+};
+
+use super::{
+    TorrentAdapter, TorrentAdapterFactory, TorrentInfo,
+    fields::{CST_OFFSET_SECS, format_size, parse_date},
+    merge_keyword_searches, padded_ep_number, release_names,
+};
+
+const MIKAN_BASE_URL: &str = "https://mikanani.me";
+
+#[derive(Deserialize)]
+struct MikanRss {
+    channel: MikanChannel,
+}
+
+#[derive(Deserialize)]
+struct MikanChannel {
+    #[serde(default)]
+    item: Vec<MikanItem>,
+}
+
+#[derive(Deserialize)]
+struct MikanItem {
+    title: String,
+    link: Option<String>,
+    torrent: Option<MikanTorrent>,
+    enclosure: Option<MikanEnclosure>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MikanTorrent {
+    content_length: Option<u64>,
+    pub_date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct MikanEnclosure {
+    #[serde(rename = "@url")]
+    url: String,
+}
+
+pub struct MikanAdapter<'a> {
+    ep: &'a Episode,
+    anime: &'a Anime,
+    client: &'a Client,
+}
+
+impl MikanAdapter<'_> {
+    #[instrument(level = "info", skip(self))]
+    async fn search_keyword(&self, keyword: String) -> KisaraResult<Vec<TorrentInfo>> {
+        info!("Searching");
+        let url = format!("{}/RSS/Search", MIKAN_BASE_URL);
+        let response = self
+            .client
+            .get(&url)
+            .query(&[("searchstr", keyword)])
+            .send()
+            .await?
+            .text()
+            .await?;
+        Self::parse(&response)
+    }
+
+    fn parse(xml: &str) -> KisaraResult<Vec<TorrentInfo>> {
+        let rss: MikanRss = quick_xml::de::from_str(xml)?;
+
+        let ret = rss
+            .channel
+            .item
+            .into_iter()
+            .filter_map(|item| {
+                // episode pages are named after the info hash, which is all a magnet needs
+                let hash = item
+                    .link
+                    .as_deref()
+                    .and_then(|link| link.rsplit('/').next())
+                    .filter(|hash| hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit()));
+                let magnet = match hash {
+                    Some(hash) => format!("magnet:?xt=urn:btih:{}", hash),
+                    None => item.enclosure?.url,
+                };
+
+                let size_bytes = item.torrent.as_ref().and_then(|t| t.content_length);
+                let size = size_bytes.map(format_size);
+
+                // mikan omits the offset, it is the site's local time
+                let pub_date = item.torrent.and_then(|t| t.pub_date);
+                let timestamp = pub_date
+                    .as_deref()
+                    .and_then(|date| parse_date(date, CST_OFFSET_SECS));
+                let date = pub_date.map(|date| {
+                    NaiveDateTime::parse_from_str(&date, "%Y-%m-%dT%H:%M:%S%.f")
+                        .map_or(date, |d| d.format("%Y-%m-%d %H:%M").to_string())
+                });
+
+                let release = ReleaseInfo::parse(&item.title);
+
+                Some(TorrentInfo {
+                    name: item.title,
+                    size,
+                    size_bytes,
+                    url: item.link,
+                    magnet,
+                    date,
+                    timestamp,
+                    seeders: None,
+                    leechers: None,
+                    uploader: None,
+                    release,
+                    source: String::new(),
+                    score: None,
+                })
+            })
+            .collect::<Vec<_>>();
+        info!("Parsed {} torrents", ret.len());
+
+        Ok(ret)
+    }
+}
+
+#[async_trait]
+impl TorrentAdapter for MikanAdapter<'_> {
+    #[instrument(level = "info", skip(self))]
+    async fn search(&self, page: u32, query: Option<&str>) -> KisaraResult<Vec<TorrentInfo>> {
+        // the search feed has no pagination, it always returns every match
+        if page > 1 {
+            return Ok(vec![]);
+        }
+
+        let keywords = query.map_or_else(
+            || {
+                let ep = padded_ep_number(self.ep);
+                release_names(self.anime)
+                    .into_iter()
+                    .map(|name| format!("{} {}", name, ep))
+                    .collect::<Vec<_>>()
+            },
+            |query| vec![query.to_owned()],
+        );
+
+        info!(?keywords);
+
+        let mut results = merge_keyword_searches(
+            keywords
+                .into_iter()
+                .map(|keyword| self.search_keyword(keyword)),
+        )
+        .await?;
+        // mikan has no peer counts, so show the newest releases first
+        results.sort_by(|a, b| b.date.cmp(&a.date));
+
+        info!("Found {} torrents", results.len());
+
+        Ok(results)
+    }
+}
+
+pub struct MikanAdapterFactory;
+
+impl MikanAdapterFactory {
+    pub const fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<'a> TorrentAdapterFactory<'a> for MikanAdapterFactory {
+    fn source_name(&self) -> String {
+        "Mikan".to_owned()
+    }
+
+    fn create_adapter(
+        &'a self,
+        ep: &'a Episode,
+        anime: &'a Anime,
+        client: &'a Client,
+    ) -> Box<dyn TorrentAdapter + Send + Sync + 'a> {
+        Box::new(MikanAdapter { ep, anime, client })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use super::MikanAdapter;
+
+    const MIKAN: &str = include_str!("fixtures/mikan.xml");
+
+    #[test]
+    fn parses_search_feed() {
+        let torrents = MikanAdapter::parse(MIKAN).expect("Failed to parse fixture");
+        // the last item has neither an info hash nor a torrent file
+        assert_eq!(torrents.len(), 2);
+
+        let torrent = &torrents[0];
+        assert_eq!(
+            torrent.name,
+            "[LoliHouse] 葬送的芙莉莲 / Sousou no Frieren - 05 [WebRip 1080p HEVC-10bit AAC][简繁内封字幕]"
+        );
+        assert_eq!(
+            torrent.magnet,
+            "magnet:?xt=urn:btih:5b0c3f8e2a7d4c1e9f6a8b3d2c1e0f9a8b7c6d5e"
+        );
+        assert_eq!(
+            torrent.url.as_deref(),
+            Some("https://mikanani.me/Home/Episode/5b0c3f8e2a7d4c1e9f6a8b3d2c1e0f9a8b7c6d5e")
+        );
+        assert_eq!(torrent.size.as_deref(), Some("336.5 MiB"));
+        assert_eq!(torrent.size_bytes, Some(352_845_824));
+        assert_eq!(torrent.date.as_deref(), Some("2023-10-06 23:47"));
+        assert_eq!(
+            torrent.timestamp,
+            DateTime::parse_from_rfc3339("2023-10-06T15:47:22.58Z")
+                .ok()
+                .map(|date| date.to_utc())
+        );
+
+        // pages not named after a hash fall back to the torrent file
+        assert_eq!(
+            torrents[1].magnet,
+            "https://mikanani.me/Download/20231007/legacy-3120.torrent"
+        );
+        assert_eq!(torrents[1].date.as_deref(), Some("2023-10-07 08:12"));
+    }
+}
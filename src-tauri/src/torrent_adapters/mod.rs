@@ -1,34 +1,76 @@
-use std::collections::HashMap;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet, hash_map::Entry},
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
+use cache::SearchCache;
+use chrono::{DateTime, Utc};
+use dmhy_adapter::DmhyAdapterFactory;
 use dummy_adapter::DummyAdapterFactory;
+use futures::future::join_all;
+use mikan_adapter::MikanAdapterFactory;
 use nyaa_adapter::NyaaAdapterFactory;
+use ranking::TorrentScore;
 use reqwest::{Client, ClientBuilder, Proxy};
+use rss_adapter::RssAdapterFactory;
 use serde::{Deserialize, Serialize};
+use torznab_adapter::TorznabAdapterFactory;
+use tracing::{info, instrument, warn};
 
+mod cache;
+mod dmhy_adapter;
 mod dummy_adapter;
+mod fields;
+mod mikan_adapter;
 mod nyaa_adapter;
+mod ranking;
+mod rss_adapter;
+#[cfg(test)]
+mod testing;
+mod torznab_adapter;
 
 use crate::{
-    data::{anime::Anime, episode::Episode},
+    data::{anime::Anime, anime_preference::AnimePreference, episode::Episode},
     error::{KisaraError, KisaraResult},
+    states::config::{RankingProfile, SearchConfig},
+    utils::release_name::ReleaseInfo,
 };
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct TorrentInfo {
     pub name: String,
+    /// Size as the source displays it.
     pub size: Option<String>,
+    pub size_bytes: Option<u64>,
     pub url: Option<String>,
     pub magnet: String,
+    /// Publish date as the source displays it.
     pub date: Option<String>,
+    pub timestamp: Option<DateTime<Utc>>,
     pub seeders: Option<u32>,
     pub leechers: Option<u32>,
     pub uploader: Option<String>,
+    pub release: ReleaseInfo,
+    /// Name of the source that listed this torrent, filled in by the registry.
+    pub source: String,
+    /// Filled in once results are ranked, see [`ranking::rank`].
+    pub score: Option<TorrentScore>,
+}
+
+impl TorrentInfo {
+    /// Release group from the name, falling back to the uploader.
+    pub fn group(&self) -> Option<&str> {
+        self.release.group.as_deref().or(self.uploader.as_deref())
+    }
 }
 
 #[async_trait]
 pub trait TorrentAdapter {
-    async fn search(&self, page: u32) -> KisaraResult<Vec<TorrentInfo>>;
+    /// Searches for the episode, or for `query` verbatim when given instead of the keywords
+    /// generated from the anime's names. Pages start at 1.
+    async fn search(&self, page: u32, query: Option<&str>) -> KisaraResult<Vec<TorrentInfo>>;
 }
 
 pub trait TorrentAdapterFactory<'a> {
@@ -36,21 +78,123 @@ pub trait TorrentAdapterFactory<'a> {
     fn priority(&self) -> u32 {
         0
     }
+    /// How long a search on this source may take before it is given up on.
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(15)
+    }
     fn create_adapter(
-        &self,
+        &'a self,
         ep: &'a Episode,
         anime: &'a Anime,
         client: &'a Client,
     ) -> Box<dyn TorrentAdapter + Send + Sync + 'a>;
 }
 
+/// Episode number as release titles usually spell it, zero-padded to two digits.
+fn padded_ep_number(ep: &Episode) -> String {
+    format!("{:02}", ep.ep.unwrap_or(ep.sort))
+}
+
+/// All names an anime may be released under: aliases, original and Chinese names and
+/// user-defined keywords, without duplicates or empty entries.
+fn release_names(anime: &Anime) -> Vec<&str> {
+    let mut names = anime
+        .aliases
+        .iter()
+        .chain([&anime.name, &anime.name_cn])
+        .chain(anime.keywords.iter())
+        .map(String::as_str)
+        .filter(|s| !s.trim().is_empty())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Awaits the searches a source runs for each of its keywords and merges their results
+/// without duplicates, most seeded first. A search that fails is logged and skipped, unless
+/// all of them failed, in which case the source failed.
+async fn merge_keyword_searches<F>(
+    searches: impl IntoIterator<Item = F>,
+) -> KisaraResult<Vec<TorrentInfo>>
+where
+    F: Future<Output = KisaraResult<Vec<TorrentInfo>>>,
+{
+    let mut results = Vec::new();
+    let mut succeeded = false;
+    let mut last_error = None;
+    for result in join_all(searches).await {
+        match result {
+            Ok(torrents) => {
+                succeeded = true;
+                results.extend(torrents);
+            }
+            Err(e) => {
+                warn!(error = %e, "Keyword search failed");
+                last_error = Some(e);
+            }
+        }
+    }
+    if !succeeded && let Some(e) = last_error {
+        return Err(e);
+    }
+
+    results.sort_unstable_by(|a, b| a.magnet.cmp(&b.magnet));
+    results.dedup_by(|a, b| a.magnet == b.magnet);
+    results.sort_unstable_by_key(|a| Reverse(a.seeders.unwrap_or(0)));
+    Ok(results)
+}
+
+/// Fails if a Torznab indexer or RSS feed is named like another source, built-in ones
+/// included, as sources are told apart by name and only the first of them would be kept.
+pub fn validate_source_names(search_config: &SearchConfig) -> KisaraResult<()> {
+    let mut names = HashSet::from([
+        DummyAdapterFactory::new().source_name(),
+        NyaaAdapterFactory::new(search_config.nyaa.clone()).source_name(),
+        MikanAdapterFactory::new().source_name(),
+        DmhyAdapterFactory::new().source_name(),
+    ]);
+    let custom = search_config
+        .torznab_indexers
+        .iter()
+        .map(|indexer| &indexer.name)
+        .chain(search_config.rss_feeds.iter().map(|feed| &feed.name));
+    for name in custom {
+        if !names.insert(name.clone()) {
+            return Err(KisaraError::DuplicateSourceName(name.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Outcome of searching a single source.
+#[derive(Serialize)]
+pub struct SourceResult {
+    pub source: String,
+    /// Number of torrents the source returned, before ranking filtered any out.
+    pub torrents: usize,
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+    /// Whether the torrents were served from the search cache.
+    pub cached: bool,
+}
+
+#[derive(Serialize)]
+pub struct SearchResults {
+    /// Ranked results of all sources that succeeded.
+    pub torrents: Vec<TorrentInfo>,
+    pub sources: Vec<SourceResult>,
+}
+
 pub struct TorrentAdapterRegistry {
     factories: HashMap<String, Box<dyn for<'a> TorrentAdapterFactory<'a> + Send + Sync>>,
     client: Client,
+    ranking_profile: RankingProfile,
+    cache: SearchCache,
 }
 
 impl TorrentAdapterRegistry {
-    pub fn new(proxy: Option<String>) -> Self {
+    pub fn new(proxy: Option<String>, search_config: SearchConfig) -> Self {
         let mut builder = ClientBuilder::new();
         if let Some(proxy_url) = proxy {
             if let Ok(p) = Proxy::all(&proxy_url) {
@@ -61,54 +205,224 @@ impl TorrentAdapterRegistry {
         let s = Self {
             factories: HashMap::new(),
             client,
+            ranking_profile: search_config.ranking_profile,
+            cache: SearchCache::new(Duration::from_secs(search_config.cache_ttl_secs)),
         };
-        s.register_adapter(DummyAdapterFactory::new())
-            .register_adapter(NyaaAdapterFactory::new())
+        let s = s
+            .register_adapter(DummyAdapterFactory::new())
+            .register_adapter(NyaaAdapterFactory::new(search_config.nyaa))
+            .register_adapter(MikanAdapterFactory::new())
+            .register_adapter(DmhyAdapterFactory::new());
+        let s = search_config
+            .torznab_indexers
+            .into_iter()
+            .fold(s, |s, indexer| {
+                s.register_adapter(TorznabAdapterFactory::new(indexer))
+            });
+        search_config.rss_feeds.into_iter().fold(s, |s, feed| {
+            s.register_adapter(RssAdapterFactory::new(feed))
+        })
     }
 
+    /// Adds a source, unless one with the same name was added before. Built-in sources are
+    /// added first, so an indexer or feed named after one is skipped rather than replace it.
+    /// Settings are checked with [`validate_source_names`], this only catches edited files.
     fn register_adapter<F>(mut self, factory: F) -> Self
     where
         F: for<'a> TorrentAdapterFactory<'a> + Send + Sync + 'static,
     {
-        let name = factory.source_name();
-        self.factories.insert(name, Box::new(factory));
+        match self.factories.entry(factory.source_name()) {
+            Entry::Occupied(entry) => {
+                warn!(
+                    source = entry.key(),
+                    "A source with this name already exists, skipping"
+                );
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(Box::new(factory));
+            }
+        }
         self
     }
 
+    /// Searches every source concurrently and ranks all results together with the configured
+    /// profile and the anime's preference. A source that fails or times out is reported in
+    /// its [`SourceResult`] and does not affect the others. Cached results are used unless
+    /// `force_refresh` is set.
     pub async fn init_search(
         &self,
         ep: &Episode,
         anime: &Anime,
-    ) -> KisaraResult<HashMap<String, Vec<TorrentInfo>>> {
-        let mut results = HashMap::new();
+        preference: Option<&AnimePreference>,
+        query: Option<&str>,
+        force_refresh: bool,
+    ) -> SearchResults {
         let mut factories_vec = self.factories.values().collect::<Vec<_>>();
         factories_vec.sort_by_key(|a| a.priority());
-        for factory in factories_vec {
-            let name = factory.source_name();
-            let adapter = factory.create_adapter(ep, anime, &self.client);
-            let torrents = adapter.search(1).await?;
-            if torrents.is_empty() {
-                continue;
-            }
-            results.insert(name, torrents);
+
+        let searches = factories_vec.into_iter().map(|factory| {
+            self.search_source(factory.as_ref(), ep, anime, 1, query, force_refresh)
+        });
+        let (sources, torrents): (Vec<_>, Vec<_>) = join_all(searches).await.into_iter().unzip();
+
+        SearchResults {
+            torrents: ranking::rank(
+                &self.ranking_profile,
+                preference,
+                torrents.into_iter().flatten().collect(),
+            ),
+            sources,
         }
-        Ok(results)
     }
 
-    #[allow(dead_code)] // TODO maybe will use it one day
+    #[instrument(level = "info", skip(self, factory, ep, anime), fields(source = factory.source_name()))]
+    async fn search_source(
+        &self,
+        factory: &(dyn for<'a> TorrentAdapterFactory<'a> + Send + Sync),
+        ep: &Episode,
+        anime: &Anime,
+        page: u32,
+        query: Option<&str>,
+        force_refresh: bool,
+    ) -> (SourceResult, Vec<TorrentInfo>) {
+        let source = factory.source_name();
+        // generated keywords change with the anime's names and keywords, a custom query
+        // replaces them
+        let cache_key = query.map_or_else(
+            || format!("ep:{}:{}", ep.id, release_names(anime).join("|")),
+            ToOwned::to_owned,
+        );
+        if !force_refresh && let Some(torrents) = self.cache.get(&source, &cache_key, page) {
+            info!(found = torrents.len(), "Serving cached results");
+            return (
+                SourceResult {
+                    source,
+                    torrents: torrents.len(),
+                    error: None,
+                    elapsed_ms: 0,
+                    cached: true,
+                },
+                torrents,
+            );
+        }
+
+        let adapter = factory.create_adapter(ep, anime, &self.client);
+        let start = Instant::now();
+        let result = tokio::time::timeout(factory.timeout(), adapter.search(page, query)).await;
+        let elapsed_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+        let (torrents, error) = match result {
+            Ok(Ok(torrents)) => (torrents, None),
+            Ok(Err(e)) => (vec![], Some(e.to_string())),
+            Err(_) => (
+                vec![],
+                Some(format!(
+                    "Timed out after {} seconds",
+                    factory.timeout().as_secs()
+                )),
+            ),
+        };
+        if let Some(ref error) = error {
+            warn!(error, elapsed_ms, "Search failed");
+        } else {
+            info!(found = torrents.len(), elapsed_ms, "Search finished");
+        }
+
+        let torrents = torrents
+            .into_iter()
+            .map(|t| TorrentInfo {
+                source: source.clone(),
+                ..t
+            })
+            .collect::<Vec<_>>();
+        // an empty page may be a hiccup of the site, which is not worth remembering
+        if error.is_none() && !torrents.is_empty() {
+            self.cache.insert(&source, &cache_key, page, &torrents);
+        }
+        (
+            SourceResult {
+                source,
+                torrents: torrents.len(),
+                error,
+                elapsed_ms,
+                cached: false,
+            },
+            torrents,
+        )
+    }
+
+    /// Fetches a single page from one source, ranked like [`Self::init_search`].
+    #[allow(clippy::too_many_arguments)]
     pub async fn search(
         &self,
         source: &str,
         ep: &Episode,
         anime: &Anime,
+        preference: Option<&AnimePreference>,
         page: u32,
-    ) -> KisaraResult<Vec<TorrentInfo>> {
-        if let Some(factory) = self.factories.get(source) {
-            let adapter = factory.create_adapter(ep, anime, &self.client);
-            let torrents = adapter.search(page).await?;
-            Ok(torrents)
-        } else {
-            Err(KisaraError::NoSuchTorrentAdapter(source.to_owned()))
+        query: Option<&str>,
+        force_refresh: bool,
+    ) -> KisaraResult<SearchResults> {
+        let factory = self
+            .factories
+            .get(source)
+            .ok_or_else(|| KisaraError::NoSuchTorrentAdapter(source.to_owned()))?;
+        let (source, torrents) = self
+            .search_source(factory.as_ref(), ep, anime, page, query, force_refresh)
+            .await;
+        Ok(SearchResults {
+            torrents: ranking::rank(&self.ranking_profile, preference, torrents),
+            sources: vec![source],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::config::{RssFeedConfig, TorznabIndexerConfig};
+
+    fn indexer(name: &str) -> TorznabIndexerConfig {
+        TorznabIndexerConfig {
+            name: name.to_owned(),
+            url: "http://127.0.0.1:9117/api".to_owned(),
+            api_key: String::new(),
+            categories: Vec::new(),
+            search_mode: Default::default(),
+        }
+    }
+
+    fn feed(name: &str) -> RssFeedConfig {
+        RssFeedConfig {
+            name: name.to_owned(),
+            url: "http://127.0.0.1/rss".to_owned(),
+        }
+    }
+
+    #[test]
+    fn rejects_duplicate_source_names() {
+        let distinct = SearchConfig {
+            torznab_indexers: vec![indexer("Jackett")],
+            rss_feeds: vec![feed("SubsPlease")],
+            ..Default::default()
+        };
+        validate_source_names(&distinct).expect("Names are distinct");
+
+        for (indexers, feeds) in [
+            (vec![indexer("Nyaa")], vec![]),
+            (vec![], vec![feed("Mikan")]),
+            (vec![indexer("Jackett"), indexer("Jackett")], vec![]),
+            (vec![indexer("SubsPlease")], vec![feed("SubsPlease")]),
+        ] {
+            let config = SearchConfig {
+                torznab_indexers: indexers,
+                rss_feeds: feeds,
+                ..Default::default()
+            };
+            assert!(matches!(
+                validate_source_names(&config),
+                Err(KisaraError::DuplicateSourceName(_))
+            ));
         }
     }
 }
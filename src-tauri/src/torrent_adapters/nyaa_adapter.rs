@@ -1,5 +1,4 @@
 use async_trait::async_trait;
-use futures::future::join_all;
 use kuchikiki::traits::TendrilSink;
 use reqwest::Client;
 use tracing::{info, instrument};
@@ -7,11 +6,18 @@ use tracing::{info, instrument};
 use crate::{
     data::{anime::Anime, episode::Episode},
     error::{KisaraError, KisaraResult},
+    states::config::NyaaConfig,
+    utils::release_name::ReleaseInfo,
 };
 
-use super::{TorrentAdapter, TorrentAdapterFactory, TorrentInfo};
+use super::{
+    TorrentAdapter, TorrentAdapterFactory, TorrentInfo,
+    fields::{parse_date, parse_size},
+    merge_keyword_searches,
+};
 
 pub struct NyaaAdapter<'a> {
+    config: &'a NyaaConfig,
     ep: &'a Episode,
     anime: &'a Anime,
     client: &'a Client,
@@ -19,14 +25,66 @@ pub struct NyaaAdapter<'a> {
 
 impl NyaaAdapter<'_> {
     #[instrument(level = "info", skip(self))]
-    async fn search_keyword(&self, keyword: String) -> KisaraResult<Vec<TorrentInfo>> {
+    async fn search_keyword(&self, keyword: String, page: u32) -> KisaraResult<Vec<TorrentInfo>> {
         info!("Searching");
-        let url = format!("https://nyaa.si/?f=0&c=1_0&q={}&s=seeders&o=desc", keyword);
-        let response = self.client.get(&url).send().await?.text().await?;
-        Self::parse(&response)
+        // free-text queries may contain `&` or `#`, so let reqwest encode them
+        let response = self
+            .client
+            .get(format!("{}/", self.base_url()))
+            .query(&[
+                ("f", self.config.filter.code()),
+                ("c", self.config.category.code()),
+                ("q", keyword.as_str()),
+                ("s", "seeders"),
+                ("o", "desc"),
+                ("p", &page.max(1).to_string()),
+            ])
+            .send()
+            .await?
+            .text()
+            .await?;
+        Self::parse(&response, self.base_url())
+    }
+
+    fn base_url(&self) -> &str {
+        self.config.base_url.trim_end_matches('/')
+    }
+
+    fn keywords(&self) -> Vec<String> {
+        let ep = self.ep.ep.unwrap_or(self.ep.sort);
+        // if ep is 1-digit, add 0 in front of it
+        let ep = if ep < 10 {
+            format!("0{}", ep)
+        } else {
+            ep.to_string()
+        };
+        let keyword = format!("{} \"{}\"", self.anime.name, ep);
+        let keyword_cn = format!("{} \"{}\"", self.anime.name_cn, ep);
+        let extra_keywords = self
+            .anime
+            .keywords
+            .iter()
+            .map(|k| format!("{}, \"{}\"", k, ep));
+
+        let mut final_keywords = self
+            .anime
+            .aliases
+            .iter()
+            .map(|alias| format!("{} \"{}\"", alias, ep))
+            .chain(vec![keyword, keyword_cn])
+            .chain(extra_keywords)
+            .collect::<Vec<_>>();
+
+        // remove duplicates
+        final_keywords.sort();
+        final_keywords.dedup();
+
+        // remove empty strings
+        final_keywords.retain(|s| !s.is_empty());
+        final_keywords
     }
 
-    fn parse(html: &str) -> KisaraResult<Vec<TorrentInfo>> {
+    fn parse(html: &str, base_url: &str) -> KisaraResult<Vec<TorrentInfo>> {
         let doc = kuchikiki::parse_html().one(html);
 
         let table = doc.select_first("table.torrent-list").map_err(|_| {
@@ -58,7 +116,7 @@ impl NyaaAdapter<'_> {
                             node.attributes
                                 .borrow()
                                 .get("href")
-                                .map(|s| format!("{}{}", "https://nyaa.si", s)),
+                                .map(|s| format!("{}{}", base_url, s)),
                         )
                     })?;
 
@@ -86,6 +144,10 @@ impl NyaaAdapter<'_> {
                     .and_then(|mut node| node.next())
                     .map(|node| node.text_contents());
 
+                let size_bytes = size.as_deref().and_then(parse_size);
+                // nyaa displays dates in UTC
+                let timestamp = date.as_deref().and_then(|d| parse_date(d, 0));
+
                 let seeders = row
                     .select("td:nth-child(6)")
                     .map_err(|_| KisaraError::HtmlParseError("Failed to select seeders".to_owned()))
@@ -102,15 +164,22 @@ impl NyaaAdapter<'_> {
                     .and_then(|mut node| node.next())
                     .and_then(|node| node.text_contents().parse::<u32>().ok());
 
+                let release = ReleaseInfo::parse(&name);
+
                 Some(TorrentInfo {
                     name,
                     size,
+                    size_bytes,
                     url,
                     magnet,
                     date,
+                    timestamp,
                     seeders,
                     leechers,
                     uploader: None,
+                    release,
+                    source: String::new(),
+                    score: None,
                 })
             })
             .collect::<Vec<_>>();
@@ -123,55 +192,17 @@ impl NyaaAdapter<'_> {
 #[async_trait]
 impl TorrentAdapter for NyaaAdapter<'_> {
     #[instrument(level = "info", skip(self))]
-    async fn search(&self, _page: u32) -> KisaraResult<Vec<TorrentInfo>> {
-        let ep = self.ep.ep.unwrap_or(self.ep.sort);
-        // if ep is 1-digit, add 0 in front of it
-        let ep = if ep < 10 {
-            format!("0{}", ep)
-        } else {
-            ep.to_string()
-        };
-        let keyword = format!("{} \"{}\"", self.anime.name, ep);
-        let keyword_cn = format!("{} \"{}\"", self.anime.name_cn, ep);
-        let extra_keywords = self
-            .anime
-            .keywords
-            .iter()
-            .map(|k| format!("{}, \"{}\"", k, ep));
-
-        let mut final_keywords = self
-            .anime
-            .aliases
-            .iter()
-            .map(|alias| format!("{} \"{}\"", alias, ep))
-            .chain(vec![keyword, keyword_cn])
-            .chain(extra_keywords)
-            .collect::<Vec<_>>();
-
-        // remove duplicates
-        final_keywords.sort();
-        final_keywords.dedup();
-
-        // remove empty strings
-        final_keywords.retain(|s| !s.is_empty());
+    async fn search(&self, page: u32, query: Option<&str>) -> KisaraResult<Vec<TorrentInfo>> {
+        let final_keywords = query.map_or_else(|| self.keywords(), |query| vec![query.to_owned()]);
 
         info!(keywords = ?final_keywords);
 
-        let mut results = join_all(
+        let results = merge_keyword_searches(
             final_keywords
                 .into_iter()
-                .map(|keyword| self.search_keyword(keyword)),
+                .map(|keyword| self.search_keyword(keyword, page)),
         )
-        .await
-        .into_iter()
-        .filter_map(Result::ok)
-        .flatten()
-        .collect::<Vec<_>>();
-
-        results.sort_unstable_by_key(|a| a.magnet.clone());
-        results.dedup_by_key(|a| a.magnet.clone());
-        results.sort_unstable_by_key(|a| a.seeders.unwrap_or(0));
-        results.reverse();
+        .await?;
 
         info!("Found {} torrents", results.len());
 
@@ -179,11 +210,13 @@ impl TorrentAdapter for NyaaAdapter<'_> {
     }
 }
 
-pub struct NyaaAdapterFactory;
+pub struct NyaaAdapterFactory {
+    config: NyaaConfig,
+}
 
 impl NyaaAdapterFactory {
-    pub const fn new() -> Self {
-        Self {}
+    pub const fn new(config: NyaaConfig) -> Self {
+        Self { config }
     }
 }
 
@@ -193,11 +226,16 @@ impl<'a> TorrentAdapterFactory<'a> for NyaaAdapterFactory {
     }
 
     fn create_adapter(
-        &self,
+        &'a self,
         ep: &'a Episode,
         anime: &'a Anime,
         client: &'a Client,
     ) -> Box<dyn TorrentAdapter + Send + Sync + 'a> {
-        Box::new(NyaaAdapter { ep, anime, client })
+        Box::new(NyaaAdapter {
+            config: &self.config,
+            ep,
+            anime,
+            client,
+        })
     }
 }
@@ -0,0 +1,431 @@
+use std::{cmp::Reverse, collections::HashSet};
+
+use librqbit::Magnet;
+use serde::{Deserialize, Serialize};
+
+use crate::{data::anime_preference::AnimePreference, states::config::RankingProfile};
+
+use super::TorrentInfo;
+
+const GROUP_WEIGHT: i32 = 50;
+const RESOLUTION_WEIGHT: i32 = 30;
+const CODEC_WEIGHT: i32 = 20;
+const SOURCE_WEIGHT: i32 = 20;
+/// Preferences of the anime itself outweigh the global profile.
+const ANIME_PREFERENCE_FACTOR: i32 = 2;
+/// Each step down a preference list is worth this much less than the one above it.
+const PREFERENCE_STEP: i32 = 5;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct TorrentScore {
+    pub value: i32,
+    /// Short notes on what contributed to `value`, e.g. `1080p preferred`.
+    pub reasons: Vec<String>,
+}
+
+impl TorrentScore {
+    fn add(&mut self, value: i32, reason: String) {
+        self.value += value;
+        self.reasons.push(reason);
+    }
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+/// Weight of the entry at `index` of a preference list, never below a single step.
+fn preference_weight(weight: i32, index: usize) -> i32 {
+    let index = i32::try_from(index).unwrap_or(i32::MAX);
+    weight
+        .saturating_sub(index.saturating_mul(PREFERENCE_STEP))
+        .max(PREFERENCE_STEP)
+}
+
+/// Why a torrent is excluded by the profile or the anime's locked group, if it is.
+fn rejection(
+    profile: &RankingProfile,
+    preference: Option<&AnimePreference>,
+    torrent: &TorrentInfo,
+) -> Option<String> {
+    if let Some(group) = preference.and_then(AnimePreference::locked_group)
+        && !torrent
+            .group()
+            .is_some_and(|g| g.eq_ignore_ascii_case(group))
+    {
+        return Some(format!("not from locked group {}", group));
+    }
+    if let Some(word) = profile
+        .required_words
+        .iter()
+        .find(|w| !contains_ignore_case(&torrent.name, w))
+    {
+        return Some(format!("missing required word {}", word));
+    }
+    if let Some(word) = profile
+        .forbidden_words
+        .iter()
+        .find(|w| contains_ignore_case(&torrent.name, w))
+    {
+        return Some(format!("forbidden word {}", word));
+    }
+    if let Some(codec) = torrent.release.video_codec.as_deref()
+        && profile
+            .excluded_codecs
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(codec))
+    {
+        return Some(format!("{} excluded", codec));
+    }
+    if let Some(bytes) = torrent.size_bytes {
+        let mib = bytes / 1024 / 1024;
+        if profile.min_size_mib.is_some_and(|min| mib < min) {
+            return Some("too small".to_owned());
+        }
+        if profile.max_size_mib.is_some_and(|max| mib > max) {
+            return Some("too large".to_owned());
+        }
+    }
+    if let (Some(min), Some(seeders)) = (profile.min_seeders, torrent.seeders)
+        && seeders < min
+    {
+        return Some(format!("only {} seeders", seeders));
+    }
+    None
+}
+
+/// Scores what the anime's preference asks for.
+fn score_preference(preference: &AnimePreference, torrent: &TorrentInfo, score: &mut TorrentScore) {
+    if let Some(group) = torrent.group()
+        && preference.release_group.is_some()
+        && preference.matches_group(torrent)
+    {
+        score.add(
+            GROUP_WEIGHT * ANIME_PREFERENCE_FACTOR,
+            format!("group {} preferred for this anime", group),
+        );
+    }
+    if let Some(resolution) = torrent.release.resolution
+        && preference.resolution.is_some()
+        && preference.matches_resolution(torrent)
+    {
+        score.add(
+            RESOLUTION_WEIGHT * ANIME_PREFERENCE_FACTOR,
+            format!("{}p preferred for this anime", resolution),
+        );
+    }
+    if let Some(source) = torrent.release.source.as_deref()
+        && preference.matches_source(torrent)
+    {
+        score.add(
+            SOURCE_WEIGHT * ANIME_PREFERENCE_FACTOR,
+            format!("{} preferred for this anime", source),
+        );
+    }
+}
+
+/// Scores a torrent against the profile and the anime's preference, or `None` if either
+/// excludes it.
+fn score(
+    profile: &RankingProfile,
+    preference: Option<&AnimePreference>,
+    torrent: &TorrentInfo,
+) -> Option<TorrentScore> {
+    if let Some(reason) = rejection(profile, preference, torrent) {
+        tracing::debug!(torrent = torrent.name, reason, "Filtered out");
+        return None;
+    }
+
+    let mut score = TorrentScore::default();
+
+    if let Some(preference) = preference {
+        score_preference(preference, torrent, &mut score);
+    }
+
+    if let Some(group) = torrent.group()
+        && let Some(index) = profile
+            .preferred_groups
+            .iter()
+            .position(|g| g.eq_ignore_ascii_case(group))
+    {
+        score.add(
+            preference_weight(GROUP_WEIGHT, index),
+            format!("group {} preferred", group),
+        );
+    }
+
+    if let Some(resolution) = torrent.release.resolution
+        && !profile.preferred_resolutions.is_empty()
+    {
+        match profile
+            .preferred_resolutions
+            .iter()
+            .position(|r| *r == resolution)
+        {
+            Some(index) => score.add(
+                preference_weight(RESOLUTION_WEIGHT, index),
+                format!("{}p preferred", resolution),
+            ),
+            None => score.add(-RESOLUTION_WEIGHT, format!("{}p not preferred", resolution)),
+        }
+    }
+
+    if let Some(codec) = torrent.release.video_codec.as_deref()
+        && let Some(index) = profile
+            .preferred_codecs
+            .iter()
+            .position(|c| c.eq_ignore_ascii_case(codec))
+    {
+        score.add(
+            preference_weight(CODEC_WEIGHT, index),
+            format!("{} preferred", codec),
+        );
+    }
+
+    Some(score)
+}
+
+/// The BitTorrent info hash of a magnet link as lowercase hex, used to spot the same release
+/// listed by several sources. Some sources spell it in base32 instead.
+fn info_hash(magnet: &str) -> Option<String> {
+    Magnet::parse(magnet)
+        .ok()
+        .and_then(|magnet| magnet.as_id20())
+        .map(|id| id.as_string())
+        .or_else(|| {
+            magnet
+                .split(['?', '&'])
+                .find_map(|param| param.strip_prefix("xt=urn:btih:"))
+                .map(str::to_lowercase)
+        })
+}
+
+/// Scores and filters torrents from all sources and merges them into a single list, best
+/// first. When sources list the same release, the best copy that was not filtered out is
+/// kept.
+pub fn rank(
+    profile: &RankingProfile,
+    preference: Option<&AnimePreference>,
+    torrents: Vec<TorrentInfo>,
+) -> Vec<TorrentInfo> {
+    let mut ranked = torrents
+        .into_iter()
+        .filter_map(|t| {
+            score(profile, preference, &t).map(|score| TorrentInfo {
+                score: Some(score),
+                ..t
+            })
+        })
+        .collect::<Vec<_>>();
+
+    ranked.sort_by_key(|t| {
+        (
+            Reverse(t.score.as_ref().map_or(0, |s| s.value)),
+            Reverse(t.seeders.unwrap_or(0)),
+            Reverse(t.timestamp),
+        )
+    });
+    // sorted first, so the copy kept is the best one
+    let mut seen = HashSet::new();
+    ranked.retain(|t| seen.insert(info_hash(&t.magnet).unwrap_or_else(|| t.magnet.clone())));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{info_hash, rank};
+    use crate::{
+        data::anime_preference::AnimePreference, states::config::RankingProfile,
+        torrent_adapters::TorrentInfo, utils::release_name::ReleaseInfo,
+    };
+
+    const HASH: &str = "0b2e1a4c3f5d6e7f8a9b0c1d2e3f4a5b6c7d8e9f";
+
+    fn torrent(name: &str, source: &str, magnet: &str) -> TorrentInfo {
+        TorrentInfo {
+            name: name.to_owned(),
+            size: None,
+            size_bytes: Some(700 * 1024 * 1024),
+            url: None,
+            magnet: magnet.to_owned(),
+            date: None,
+            timestamp: None,
+            seeders: Some(100),
+            leechers: None,
+            uploader: None,
+            release: ReleaseInfo::parse(name),
+            source: source.to_owned(),
+            score: None,
+        }
+    }
+
+    fn names(ranked: &[TorrentInfo]) -> Vec<&str> {
+        ranked.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    #[test]
+    fn normalises_info_hashes() {
+        assert_eq!(
+            info_hash("magnet:?xt=urn:btih:0B2E1A4C3F5D6E7F8A9B0C1D2E3F4A5B6C7D8E9F&dn=Frieren")
+                .as_deref(),
+            Some(HASH)
+        );
+        assert_eq!(
+            info_hash(
+                "magnet:?xt=urn:btih:BMXBUTB7LVXH7CU3BQOS4P2KLNWH3DU7&tr=udp%3A%2F%2Ftracker"
+            )
+            .as_deref(),
+            Some(HASH)
+        );
+        assert_eq!(info_hash("https://nyaa.si/download/1712345.torrent"), None);
+    }
+
+    #[test]
+    fn rejects_what_the_profile_excludes() {
+        let profile = RankingProfile {
+            required_words: vec!["frieren".to_owned()],
+            forbidden_words: vec!["Dub".to_owned()],
+            excluded_codecs: vec!["hevc".to_owned()],
+            min_size_mib: Some(100),
+            max_size_mib: Some(2048),
+            min_seeders: Some(5),
+            ..Default::default()
+        };
+        let mut tiny = torrent("[Tiny] Sousou no Frieren - 05 [1080p]", "Nyaa", "magnet:1");
+        tiny.size_bytes = Some(1024 * 1024);
+        let mut huge = torrent("[Huge] Sousou no Frieren - 05 [1080p]", "Nyaa", "magnet:2");
+        huge.size_bytes = Some(4096 * 1024 * 1024);
+        let mut dead = torrent("[Dead] Sousou no Frieren - 05 [1080p]", "Nyaa", "magnet:3");
+        dead.seeders = Some(1);
+        let torrents = vec![
+            torrent(
+                "[SubsPlease] Sousou no Frieren - 05 (1080p)",
+                "Nyaa",
+                "magnet:4",
+            ),
+            torrent("[SubsPlease] Other Show - 05 (1080p)", "Nyaa", "magnet:5"),
+            torrent(
+                "[Group] Sousou no Frieren - 05 (1080p) [Dub]",
+                "Nyaa",
+                "magnet:6",
+            ),
+            torrent(
+                "[Group] Sousou no Frieren - 05 [1080p HEVC]",
+                "Nyaa",
+                "magnet:7",
+            ),
+            tiny,
+            huge,
+            dead,
+        ];
+
+        let ranked = rank(&profile, None, torrents);
+        assert_eq!(
+            names(&ranked),
+            ["[SubsPlease] Sousou no Frieren - 05 (1080p)"]
+        );
+    }
+
+    #[test]
+    fn rejects_other_groups_when_locked() {
+        let preference = AnimePreference {
+            anime_id: 1,
+            release_group: Some("subsplease".to_owned()),
+            resolution: None,
+            source: None,
+            lock_group: true,
+        };
+        let torrents = vec![
+            torrent(
+                "[Erai-raws] Sousou no Frieren - 05 [1080p]",
+                "Nyaa",
+                "magnet:1",
+            ),
+            torrent(
+                "[SubsPlease] Sousou no Frieren - 05 (1080p)",
+                "Nyaa",
+                "magnet:2",
+            ),
+        ];
+
+        let ranked = rank(&RankingProfile::default(), Some(&preference), torrents);
+        assert_eq!(
+            names(&ranked),
+            ["[SubsPlease] Sousou no Frieren - 05 (1080p)"]
+        );
+    }
+
+    #[test]
+    fn orders_by_score_then_seeders() {
+        let profile = RankingProfile {
+            preferred_groups: vec!["SubsPlease".to_owned(), "Erai-raws".to_owned()],
+            preferred_resolutions: vec![1080, 720],
+            ..Default::default()
+        };
+        let mut popular = torrent("[Other] Sousou no Frieren - 05 [1080p]", "Nyaa", "magnet:1");
+        popular.seeders = Some(5000);
+        let torrents = vec![
+            torrent("[Other] Sousou no Frieren - 05 [480p]", "Nyaa", "magnet:2"),
+            torrent("[Other] Sousou no Frieren - 05 [1080p]", "Nyaa", "magnet:3"),
+            torrent(
+                "[Erai-raws] Sousou no Frieren - 05 [720p]",
+                "Nyaa",
+                "magnet:4",
+            ),
+            torrent(
+                "[SubsPlease] Sousou no Frieren - 05 (720p)",
+                "Nyaa",
+                "magnet:5",
+            ),
+            torrent(
+                "[SubsPlease] Sousou no Frieren - 05 (1080p)",
+                "Nyaa",
+                "magnet:6",
+            ),
+            popular,
+        ];
+
+        let ranked = rank(&profile, None, torrents);
+        assert_eq!(
+            names(&ranked),
+            [
+                "[SubsPlease] Sousou no Frieren - 05 (1080p)",
+                "[SubsPlease] Sousou no Frieren - 05 (720p)",
+                "[Erai-raws] Sousou no Frieren - 05 [720p]",
+                "[Other] Sousou no Frieren - 05 [1080p]",
+                "[Other] Sousou no Frieren - 05 [1080p]",
+                "[Other] Sousou no Frieren - 05 [480p]",
+            ]
+        );
+        assert_eq!(ranked[3].seeders, Some(5000));
+        assert!(
+            ranked
+                .windows(2)
+                .all(|w| w[0].score.as_ref().map(|s| s.value)
+                    >= w[1].score.as_ref().map(|s| s.value))
+        );
+    }
+
+    #[test]
+    fn keeps_an_accepted_copy_of_a_rejected_duplicate() {
+        let profile = RankingProfile {
+            min_seeders: Some(10),
+            ..Default::default()
+        };
+        let name = "[SubsPlease] Sousou no Frieren - 05 (1080p)";
+        let mut unseeded = torrent(name, "Mikan", &format!("magnet:?xt=urn:btih:{HASH}"));
+        unseeded.seeders = Some(0);
+        let mut seeded = torrent(
+            name,
+            "Nyaa",
+            "magnet:?xt=urn:btih:BMXBUTB7LVXH7CU3BQOS4P2KLNWH3DU7",
+        );
+        seeded.seeders = Some(50);
+        let mut better = torrent(name, "Dmhy", &format!("magnet:?xt=urn:btih:{HASH}&dn=x"));
+        better.seeders = Some(80);
+
+        let ranked = rank(&profile, None, vec![unseeded, seeded, better]);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].source, "Dmhy");
+    }
+}
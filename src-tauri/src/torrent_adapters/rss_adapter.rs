@@ -0,0 +1,401 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::{info, instrument};
+
+use crate::{
+    data::{anime::Anime, episode::Episode},
+    error::KisaraResult,
+    states::config::RssFeedConfig,
+    utils::release_name::ReleaseInfo,
+};
+
+use super::{
+    TorrentAdapter, TorrentAdapterFactory, TorrentInfo,
+    fields::{format_feed_date, format_size, parse_date, parse_size},
+    release_names,
+};
+
+#[derive(Deserialize)]
+struct Rss {
+    channel: RssChannel,
+}
+
+#[derive(Deserialize)]
+struct RssChannel {
+    #[serde(default)]
+    item: Vec<RssItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RssItem {
+    title: String,
+    link: Option<String>,
+    guid: Option<String>,
+    pub_date: Option<String>,
+    enclosure: Option<RssEnclosure>,
+    // nyaa and its clones add these to their feeds as `nyaa:seeders` and so on, quick-xml
+    // matches elements by local name
+    seeders: Option<u32>,
+    leechers: Option<u32>,
+    size: Option<String>,
+    info_hash: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RssEnclosure {
+    #[serde(rename = "@url")]
+    url: String,
+    #[serde(rename = "@length")]
+    length: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct AtomFeed {
+    #[serde(default)]
+    entry: Vec<AtomEntry>,
+}
+
+#[derive(Deserialize)]
+struct AtomEntry {
+    title: String,
+    updated: Option<String>,
+    #[serde(default)]
+    link: Vec<AtomLink>,
+}
+
+#[derive(Deserialize)]
+struct AtomLink {
+    #[serde(rename = "@href")]
+    href: String,
+    #[serde(rename = "@rel")]
+    rel: Option<String>,
+    #[serde(rename = "@length")]
+    length: Option<u64>,
+}
+
+pub struct RssAdapter<'a> {
+    feed: &'a RssFeedConfig,
+    ep: &'a Episode,
+    anime: &'a Anime,
+    client: &'a Client,
+}
+
+impl RssAdapter<'_> {
+    fn parse(xml: &str) -> KisaraResult<Vec<TorrentInfo>> {
+        let ret = match quick_xml::de::from_str::<Rss>(xml) {
+            Ok(rss) => rss
+                .channel
+                .item
+                .into_iter()
+                .filter_map(Self::rss_item_to_torrent)
+                .collect::<Vec<_>>(),
+            Err(rss_err) => quick_xml::de::from_str::<AtomFeed>(xml)
+                .map_err(|_| rss_err)?
+                .entry
+                .into_iter()
+                .filter_map(Self::atom_entry_to_torrent)
+                .collect::<Vec<_>>(),
+        };
+        info!("Parsed {} torrents", ret.len());
+
+        Ok(ret)
+    }
+
+    fn rss_item_to_torrent(item: RssItem) -> Option<TorrentInfo> {
+        let link_is_torrent = item
+            .link
+            .as_deref()
+            .is_some_and(|link| link.starts_with("magnet:") || is_torrent_file(link));
+        // nyaa-style feeds link the torrent file and keep the page in the guid
+        let (magnet, url) = if link_is_torrent {
+            (item.link?, item.guid)
+        } else if let Some(ref enclosure) = item.enclosure {
+            (enclosure.url.clone(), item.link.or(item.guid))
+        } else {
+            let magnet = format!("magnet:?xt=urn:btih:{}", item.info_hash.as_deref()?);
+            (magnet, item.link.or(item.guid))
+        };
+
+        let length = item.enclosure.and_then(|e| e.length);
+        let size_bytes = item.size.as_deref().and_then(parse_size).or(length);
+        let size = item.size.or_else(|| length.map(format_size));
+
+        let release = ReleaseInfo::parse(&item.title);
+
+        Some(TorrentInfo {
+            name: item.title,
+            size,
+            size_bytes,
+            url,
+            magnet,
+            date: item.pub_date.as_deref().map(format_feed_date),
+            timestamp: item.pub_date.as_deref().and_then(|d| parse_date(d, 0)),
+            seeders: item.seeders,
+            leechers: item.leechers,
+            uploader: None,
+            release,
+            source: String::new(),
+            score: None,
+        })
+    }
+
+    fn atom_entry_to_torrent(entry: AtomEntry) -> Option<TorrentInfo> {
+        let torrent_link = entry.link.iter().find(|l| {
+            l.href.starts_with("magnet:")
+                || l.rel.as_deref() == Some("enclosure")
+                || is_torrent_file(&l.href)
+        })?;
+        let url = entry
+            .link
+            .iter()
+            .find(|l| {
+                l.rel.as_deref().is_none_or(|rel| rel == "alternate")
+                    && !l.href.starts_with("magnet:")
+                    && !is_torrent_file(&l.href)
+            })
+            .map(|l| l.href.clone());
+
+        let release = ReleaseInfo::parse(&entry.title);
+
+        Some(TorrentInfo {
+            name: entry.title,
+            size: torrent_link.length.map(format_size),
+            size_bytes: torrent_link.length,
+            url,
+            magnet: torrent_link.href.clone(),
+            date: entry.updated.as_deref().map(format_feed_date),
+            timestamp: entry.updated.as_deref().and_then(|d| parse_date(d, 0)),
+            seeders: None,
+            leechers: None,
+            uploader: None,
+            release,
+            source: String::new(),
+            score: None,
+        })
+    }
+}
+
+/// Whether `url` points to a torrent file, ignoring its query.
+fn is_torrent_file(url: &str) -> bool {
+    url.split(['?', '#'])
+        .next()
+        .is_some_and(|path| path.to_lowercase().ends_with(".torrent"))
+}
+
+#[async_trait]
+impl TorrentAdapter for RssAdapter<'_> {
+    #[instrument(level = "info", skip(self), fields(feed = self.feed.name))]
+    async fn search(&self, page: u32, query: Option<&str>) -> KisaraResult<Vec<TorrentInfo>> {
+        // a feed is a single page of the latest releases
+        if page > 1 {
+            return Ok(vec![]);
+        }
+
+        info!("Fetching feed");
+        let response = self
+            .client
+            .get(&self.feed.url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let torrents = Self::parse(&response)?.into_iter();
+        let results = if let Some(query) = query {
+            // feeds cannot be searched, so keep entries containing every word of the query
+            let words = query
+                .split_whitespace()
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>();
+            torrents
+                .filter(|t| {
+                    let title = t.name.to_lowercase();
+                    words.iter().all(|word| title.contains(word.as_str()))
+                })
+                .collect::<Vec<_>>()
+        } else {
+            let names = release_names(self.anime)
+                .into_iter()
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>();
+            let ep = self.ep.ep.unwrap_or(self.ep.sort);
+            torrents
+                .filter(|t| {
+                    let title = t.name.to_lowercase();
+                    names.iter().any(|name| title.contains(name.as_str()))
+                        && t.release.contains_episode(ep)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        info!("Found {} torrents", results.len());
+
+        Ok(results)
+    }
+}
+
+/// One factory per configured feed, so each shows up as its own source.
+pub struct RssAdapterFactory {
+    feed: RssFeedConfig,
+}
+
+impl RssAdapterFactory {
+    pub const fn new(feed: RssFeedConfig) -> Self {
+        Self { feed }
+    }
+}
+
+impl<'a> TorrentAdapterFactory<'a> for RssAdapterFactory {
+    fn source_name(&self) -> String {
+        self.feed.name.clone()
+    }
+
+    fn priority(&self) -> u32 {
+        2
+    }
+
+    fn create_adapter(
+        &'a self,
+        ep: &'a Episode,
+        anime: &'a Anime,
+        client: &'a Client,
+    ) -> Box<dyn TorrentAdapter + Send + Sync + 'a> {
+        Box::new(RssAdapter {
+            feed: &self.feed,
+            ep,
+            anime,
+            client,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Client;
+    use tauri::async_runtime::block_on;
+
+    use crate::{
+        states::config::RssFeedConfig,
+        torrent_adapters::{
+            TorrentAdapterFactory,
+            testing::{self, StandInServer},
+        },
+    };
+
+    use super::{RssAdapter, RssAdapterFactory};
+
+    const NYAA_RSS: &str = include_str!("fixtures/nyaa_rss.xml");
+    const GROUP_RSS: &str = include_str!("fixtures/group_rss.xml");
+    const ATOM: &str = include_str!("fixtures/atom.xml");
+
+    #[test]
+    fn parses_nyaa_feed() {
+        let torrents = RssAdapter::parse(NYAA_RSS).expect("Failed to parse fixture");
+        assert_eq!(torrents.len(), 3);
+
+        let torrent = &torrents[0];
+        assert_eq!(torrent.magnet, "https://nyaa.si/download/1712345.torrent");
+        assert_eq!(torrent.url.as_deref(), Some("https://nyaa.si/view/1712345"));
+        assert_eq!(torrent.seeders, Some(812));
+        assert_eq!(torrent.leechers, Some(31));
+        assert_eq!(torrent.size.as_deref(), Some("1.4 GiB"));
+        assert_eq!(torrent.size_bytes, Some(1_503_238_553));
+        assert!(torrent.timestamp.is_some());
+    }
+
+    #[test]
+    fn parses_enclosures_magnets_and_info_hashes() {
+        let torrents = RssAdapter::parse(GROUP_RSS).expect("Failed to parse fixture");
+        // the announcement has nothing to download
+        assert_eq!(torrents.len(), 3);
+
+        assert_eq!(
+            torrents[0].magnet,
+            "https://releases.example.org/torrents/305.torrent"
+        );
+        assert_eq!(
+            torrents[0].url.as_deref(),
+            Some("https://releases.example.org/view/305")
+        );
+        assert_eq!(torrents[0].size_bytes, Some(734_003_200));
+
+        assert!(
+            torrents[1]
+                .magnet
+                .starts_with("magnet:?xt=urn:btih:3e5a7c9e1b3d5f7a9c1e3b5d7f9a1c3e5b7d9f1a")
+        );
+        assert_eq!(
+            torrents[1].url.as_deref(),
+            Some("https://releases.example.org/view/306")
+        );
+
+        assert_eq!(
+            torrents[2].magnet,
+            "magnet:?xt=urn:btih:4f6b8d0f2c4e6a8b0d2f4a6c8e0b2d4f6a8c0e2b"
+        );
+        assert_eq!(
+            torrents[2].url.as_deref(),
+            Some("https://releases.example.org/view/307")
+        );
+    }
+
+    #[test]
+    fn parses_atom_feed() {
+        let torrents = RssAdapter::parse(ATOM).expect("Failed to parse fixture");
+        assert_eq!(torrents.len(), 3);
+
+        assert_eq!(
+            torrents[0].magnet,
+            "https://releases.example.net/torrents/9001.torrent"
+        );
+        assert_eq!(
+            torrents[0].url.as_deref(),
+            Some("https://releases.example.net/entries/9001")
+        );
+        assert_eq!(torrents[0].size_bytes, Some(641_728_512));
+
+        assert_eq!(
+            torrents[1].magnet,
+            "magnet:?xt=urn:btih:5a7c9e1b3d5f7a9c1e3b5d7f9a1c3e5b7d9f1a3c"
+        );
+        assert_eq!(
+            torrents[1].url.as_deref(),
+            Some("https://releases.example.net/entries/8950")
+        );
+
+        assert_eq!(
+            torrents[2].magnet,
+            "https://releases.example.net/download/8900.torrent?passkey=abc"
+        );
+        assert_eq!(torrents[2].url, None);
+    }
+
+    #[test]
+    fn filters_stand_in_feed_by_anime_and_episode() {
+        let server = StandInServer::start("200 OK", NYAA_RSS);
+        let factory = RssAdapterFactory::new(RssFeedConfig {
+            name: "Nyaa feed".to_owned(),
+            url: format!("{}/?page=rss", server.url),
+        });
+        let (anime, ep, client) = (testing::anime(), testing::episode(5), Client::new());
+        let adapter = factory.create_adapter(&ep, &anime, &client);
+
+        let torrents = block_on(adapter.search(1, None)).expect("Search failed");
+        assert_eq!(torrents.len(), 1);
+        assert_eq!(
+            torrents[0].magnet,
+            "https://nyaa.si/download/1712345.torrent"
+        );
+        assert_eq!(server.requests(), ["/?page=rss"]);
+
+        // feeds have a single page
+        assert!(
+            block_on(adapter.search(2, None))
+                .expect("Search failed")
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use crate::data::{anime::Anime, episode::Episode};
+
+/// A local stand-in for a torrent site, answering every request with the same response.
+pub struct StandInServer {
+    pub url: String,
+    /// Request targets received so far, path and query.
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl StandInServer {
+    pub fn start(status: &'static str, body: &'static str) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind stand-in server");
+        let url = format!(
+            "http://{}",
+            listener.local_addr().expect("Failed to get address")
+        );
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let received = Arc::clone(&requests);
+        thread::spawn(move || {
+            for socket in listener.incoming().map_while(Result::ok) {
+                let mut reader = BufReader::new(&socket);
+                let mut line = String::new();
+                if reader.read_line(&mut line).is_err() {
+                    continue;
+                }
+                if let Some(target) = line.split_whitespace().nth(1) {
+                    received
+                        .lock()
+                        .expect("Requests poisoned")
+                        .push(target.to_owned());
+                }
+                // the request has no body, so the headers end it
+                let mut header = String::new();
+                while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+                    header.clear();
+                }
+                let response = format!(
+                    "HTTP/1.1 {status}\r\n\
+                    Content-Type: application/xml\r\n\
+                    Content-Length: {}\r\n\
+                    Connection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = (&socket).write_all(response.as_bytes());
+            }
+        });
+        Self { url, requests }
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().expect("Requests poisoned").clone()
+    }
+}
+
+pub fn anime() -> Anime {
+    Anime {
+        id: 400_602,
+        name: "葬送のフリーレン".to_owned(),
+        aliases: vec!["Sousou no Frieren".to_owned()],
+        name_cn: "葬送的芙莉莲".to_owned(),
+        image: String::new(),
+        release_date: None,
+        keywords: vec![],
+    }
+}
+
+pub fn episode(ep: i32) -> Episode {
+    Episode {
+        id: 1_000 + ep,
+        anime_id: 400_602,
+        sort: ep,
+        ep: Some(ep),
+        name: String::new(),
+        name_cn: String::new(),
+        air_date: None,
+        progress: 0,
+        last_watch_time: None,
+        torrent_id: None,
+    }
+}
@@ -0,0 +1,347 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::{info, instrument};
+
+use crate::{
+    data::{anime::Anime, episode::Episode},
+    error::KisaraResult,
+    states::config::{TorznabIndexerConfig, TorznabSearchMode},
+    utils::release_name::ReleaseInfo,
+};
+
+use super::{
+    TorrentAdapter, TorrentAdapterFactory, TorrentInfo,
+    fields::{format_feed_date, format_size, parse_date},
+    merge_keyword_searches, padded_ep_number, release_names,
+};
+
+const PAGE_SIZE: u32 = 100;
+
+#[derive(Deserialize)]
+struct TorznabRss {
+    channel: TorznabChannel,
+}
+
+#[derive(Deserialize)]
+struct TorznabChannel {
+    #[serde(default)]
+    item: Vec<TorznabItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TorznabItem {
+    title: String,
+    link: Option<String>,
+    comments: Option<String>,
+    pub_date: Option<String>,
+    size: Option<u64>,
+    enclosure: Option<TorznabEnclosure>,
+    // quick-xml matches elements by local name, so this is `torznab:attr`
+    #[serde(rename = "attr", default)]
+    attrs: Vec<TorznabAttr>,
+}
+
+impl TorznabItem {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|a| a.name == name)
+            .map(|a| a.value.as_str())
+    }
+}
+
+#[derive(Deserialize)]
+struct TorznabEnclosure {
+    #[serde(rename = "@url")]
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct TorznabAttr {
+    #[serde(rename = "@name")]
+    name: String,
+    #[serde(rename = "@value")]
+    value: String,
+}
+
+pub struct TorznabAdapter<'a> {
+    indexer: &'a TorznabIndexerConfig,
+    ep: &'a Episode,
+    anime: &'a Anime,
+    client: &'a Client,
+}
+
+impl TorznabAdapter<'_> {
+    /// Searches for `keyword`, plus the episode when `ep` is set.
+    #[instrument(level = "info", skip(self), fields(indexer = self.indexer.name))]
+    async fn search_keyword(
+        &self,
+        keyword: &str,
+        ep: Option<&str>,
+        page: u32,
+    ) -> KisaraResult<Vec<TorrentInfo>> {
+        info!("Searching");
+        let offset = page.saturating_sub(1) * PAGE_SIZE;
+        let mut query = vec![
+            ("apikey", self.indexer.api_key.clone()),
+            ("limit", PAGE_SIZE.to_string()),
+            ("offset", offset.to_string()),
+        ];
+        match (self.indexer.search_mode, ep) {
+            (_, None) => {
+                query.push(("t", "search".to_owned()));
+                query.push(("q", keyword.to_owned()));
+            }
+            (TorznabSearchMode::Search, Some(ep)) => {
+                query.push(("t", "search".to_owned()));
+                query.push(("q", format!("{} {}", keyword, ep)));
+            }
+            (TorznabSearchMode::TvSearch, Some(ep)) => {
+                query.push(("t", "tvsearch".to_owned()));
+                query.push(("q", keyword.to_owned()));
+                query.push(("ep", ep.to_owned()));
+            }
+        }
+        if !self.indexer.categories.is_empty() {
+            query.push(("cat", self.indexer.categories.iter().join(",")));
+        }
+
+        let response = self
+            .client
+            .get(&self.indexer.url)
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        Self::parse(&response)
+    }
+
+    fn parse(xml: &str) -> KisaraResult<Vec<TorrentInfo>> {
+        let rss: TorznabRss = quick_xml::de::from_str(xml)?;
+
+        let ret = rss
+            .channel
+            .item
+            .into_iter()
+            .filter_map(|item| {
+                let seeders = item.attr("seeders").and_then(|s| s.parse::<u32>().ok());
+                // torznab reports peers as seeders plus leechers
+                let leechers = item
+                    .attr("leechers")
+                    .and_then(|s| s.parse::<u32>().ok())
+                    .or_else(|| {
+                        item.attr("peers")
+                            .and_then(|s| s.parse::<u32>().ok())
+                            .map(|peers| peers.saturating_sub(seeders.unwrap_or(0)))
+                    });
+                let size_bytes = item
+                    .size
+                    .or_else(|| item.attr("size").and_then(|s| s.parse::<u64>().ok()));
+                let size = size_bytes.map(format_size);
+                let date = item.pub_date.as_deref().map(format_feed_date);
+                let timestamp = item.pub_date.as_deref().and_then(|d| parse_date(d, 0));
+                let uploader = item.attr("team").map(ToOwned::to_owned);
+
+                // prefer a real magnet, the enclosure is usually a download link served by the indexer
+                let magnet = item
+                    .attr("magneturl")
+                    .map(ToOwned::to_owned)
+                    .or_else(|| {
+                        item.attr("infohash")
+                            .map(|hash| format!("magnet:?xt=urn:btih:{}", hash))
+                    })
+                    .or_else(|| item.enclosure.map(|e| e.url))
+                    .or_else(|| item.link.clone())?;
+
+                let release = ReleaseInfo::parse(&item.title);
+
+                Some(TorrentInfo {
+                    name: item.title,
+                    size,
+                    size_bytes,
+                    url: item.comments.or(item.link),
+                    magnet,
+                    date,
+                    timestamp,
+                    seeders,
+                    leechers,
+                    uploader,
+                    release,
+                    source: String::new(),
+                    score: None,
+                })
+            })
+            .collect::<Vec<_>>();
+        info!("Parsed {} torrents", ret.len());
+
+        Ok(ret)
+    }
+}
+
+#[async_trait]
+impl TorrentAdapter for TorznabAdapter<'_> {
+    #[instrument(level = "info", skip(self), fields(indexer = self.indexer.name))]
+    async fn search(&self, page: u32, query: Option<&str>) -> KisaraResult<Vec<TorrentInfo>> {
+        let (keywords, ep) = query.map_or_else(
+            || (release_names(self.anime), Some(padded_ep_number(self.ep))),
+            |query| (vec![query], None),
+        );
+
+        info!(?keywords);
+
+        let results = merge_keyword_searches(
+            keywords
+                .into_iter()
+                .map(|keyword| self.search_keyword(keyword, ep.as_deref(), page)),
+        )
+        .await?;
+
+        info!("Found {} torrents", results.len());
+
+        Ok(results)
+    }
+}
+
+/// One factory per configured indexer, so each shows up as its own source.
+pub struct TorznabAdapterFactory {
+    indexer: TorznabIndexerConfig,
+}
+
+impl TorznabAdapterFactory {
+    pub const fn new(indexer: TorznabIndexerConfig) -> Self {
+        Self { indexer }
+    }
+}
+
+impl<'a> TorrentAdapterFactory<'a> for TorznabAdapterFactory {
+    fn source_name(&self) -> String {
+        self.indexer.name.clone()
+    }
+
+    fn priority(&self) -> u32 {
+        1
+    }
+
+    // indexer proxies like Jackett query the upstream site on every request
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(30)
+    }
+
+    fn create_adapter(
+        &'a self,
+        ep: &'a Episode,
+        anime: &'a Anime,
+        client: &'a Client,
+    ) -> Box<dyn TorrentAdapter + Send + Sync + 'a> {
+        Box::new(TorznabAdapter {
+            indexer: &self.indexer,
+            ep,
+            anime,
+            client,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Client;
+    use tauri::async_runtime::block_on;
+
+    use crate::{
+        states::config::{TorznabIndexerConfig, TorznabSearchMode},
+        torrent_adapters::{
+            TorrentAdapterFactory,
+            testing::{self, StandInServer},
+        },
+    };
+
+    use super::{TorznabAdapter, TorznabAdapterFactory};
+
+    const RESPONSE: &str = include_str!("fixtures/torznab.xml");
+
+    #[test]
+    fn parses_torznab_attributes() {
+        let torrents = TorznabAdapter::parse(RESPONSE).expect("Failed to parse fixture");
+        assert_eq!(torrents.len(), 2);
+
+        let subsplease = &torrents[0];
+        assert_eq!(subsplease.seeders, Some(812));
+        assert_eq!(subsplease.leechers, Some(31));
+        assert_eq!(subsplease.size_bytes, Some(1_449_551_462));
+        assert!(
+            subsplease
+                .magnet
+                .starts_with("magnet:?xt=urn:btih:0b2e1a4c3f5d6e7f8a9b0c1d2e3f4a5b6c7d8e9f")
+        );
+        assert_eq!(
+            subsplease.url.as_deref(),
+            Some("https://nyaa.si/view/1712345")
+        );
+        assert_eq!(subsplease.release.group.as_deref(), Some("SubsPlease"));
+
+        // only torznab attributes, no enclosure or link
+        let erai = &torrents[1];
+        assert_eq!(
+            erai.magnet,
+            "magnet:?xt=urn:btih:9f8e7d6c5b4a3f2e1d0c9b8a7f6e5d4c3b2a1f0e"
+        );
+        assert_eq!(erai.seeders, Some(120));
+        assert_eq!(erai.leechers, Some(7));
+        assert_eq!(erai.size_bytes, Some(734_003_200));
+        assert_eq!(erai.uploader.as_deref(), Some("Erai-raws"));
+    }
+
+    #[test]
+    fn searches_stand_in_indexer() {
+        let server = StandInServer::start("200 OK", RESPONSE);
+        let factory = TorznabAdapterFactory::new(TorznabIndexerConfig {
+            name: "Jackett".to_owned(),
+            url: format!("{}/api/v2.0/indexers/nyaasi/results/torznab", server.url),
+            api_key: "key".to_owned(),
+            categories: vec![5070],
+            search_mode: TorznabSearchMode::TvSearch,
+        });
+        let (anime, ep, client) = (testing::anime(), testing::episode(5), Client::new());
+        let adapter = factory.create_adapter(&ep, &anime, &client);
+
+        let torrents = block_on(adapter.search(2, None)).expect("Search failed");
+        assert_eq!(torrents.len(), 2);
+        // sorted by seeders
+        assert_eq!(torrents[0].seeders, Some(812));
+
+        let requests = server.requests();
+        assert!(!requests.is_empty());
+        for request in requests {
+            assert!(request.starts_with("/api/v2.0/indexers/nyaasi/results/torznab?"));
+            assert!(request.contains("apikey=key"));
+            assert!(request.contains("t=tvsearch"));
+            assert!(request.contains("ep=05"));
+            assert!(request.contains("offset=100"));
+            assert!(request.contains("cat=5070"));
+        }
+    }
+
+    #[test]
+    fn reports_failing_indexer() {
+        let server = StandInServer::start("503 Service Unavailable", "");
+        let factory = TorznabAdapterFactory::new(TorznabIndexerConfig {
+            name: "Jackett".to_owned(),
+            url: server.url,
+            api_key: "key".to_owned(),
+            categories: vec![],
+            search_mode: TorznabSearchMode::Search,
+        });
+        let (anime, ep, client) = (testing::anime(), testing::episode(5), Client::new());
+        let adapter = factory.create_adapter(&ep, &anime, &client);
+
+        // an indexer that is down is an error, not an empty result
+        assert!(block_on(adapter.search(1, None)).is_err());
+    }
+}
@@ -1,3 +1,6 @@
+pub mod disk;
+pub mod release_name;
+pub mod relocate;
 pub mod season;
 pub mod subtitle;
 pub mod video;
@@ -0,0 +1,893 @@
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+/// What [`ReleaseInfo::source`] may be.
+pub const SOURCES: [&str; 4] = ["BD", "WEB", "TV", "DVD"];
+
+/// Inclusive range of episodes a release contains, `start == end` for a single episode.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpisodeRange {
+    pub start: i32,
+    pub end: i32,
+}
+
+impl EpisodeRange {
+    const fn single(ep: i32) -> Self {
+        Self { start: ep, end: ep }
+    }
+
+    pub const fn contains(&self, ep: i32) -> bool {
+        self.start <= ep && ep <= self.end
+    }
+}
+
+/// Structured metadata parsed from a release or file name such as
+/// `[SubsPlease] Title - 05 (1080p) [ABCD1234].mkv` or `【喵萌奶茶屋】★04月新番★[标题][05][1080p][简日双语]`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReleaseInfo {
+    pub group: Option<String>,
+    pub title: Option<String>,
+    pub episodes: Option<EpisodeRange>,
+    pub season: Option<u32>,
+    /// Vertical resolution, e.g. `1080` for both `1080p` and `1920x1080`.
+    pub resolution: Option<u32>,
+    /// One of [`SOURCES`].
+    pub source: Option<String>,
+    /// One of `HEVC`, `AVC`, `AV1` or `VP9`.
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    /// Subtitle or audio languages as BCP 47 tags (`zh-Hans`, `zh-Hant`, `ja`, ...), `multi`
+    /// for multi-subs releases.
+    pub languages: Vec<String>,
+    pub version: Option<u32>,
+    pub crc: Option<String>,
+}
+
+static EXTENSION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\.(mkv|mp4|avi|ts|m2ts|webm|flv|wmv|rmvb|mov|torrent)$").expect("valid regex")
+});
+static BRACKET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[\[【(（★]([^\[\]【】()（）★]*)[\]】)）★]").expect("valid regex")
+});
+static DOTTED_CODEC: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)h\.(26[45])").expect("valid regex"));
+static SCENE_GROUP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"-([A-Za-z0-9]+)$").expect("valid regex"));
+
+static EP_SEASON: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)S(\d{1,2})E(\d{1,4})(?:-?E(\d{1,4}))?(?:v(\d))?").expect("valid regex")
+});
+static EP_CN_RANGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"第\s*(\d{1,4})\s*[-~～至]\s*(\d{1,4})\s*[话話集]").expect("valid regex")
+});
+static EP_CN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"第\s*(\d{1,4})\s*[话話集](?:v(\d))?").expect("valid regex"));
+static EP_DASH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\s[-–—]\s+(\d{1,4})(?:\.\d)?(?:\s*[-~～]\s*(\d{1,4}))?(?:v(\d))?(?:\s|$)")
+        .expect("valid regex")
+});
+static EP_PREFIXED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:^|\s)(?:EP?|#)\.?\s?(\d{1,4})(?:v(\d))?(?:\s|$)").expect("valid regex")
+});
+// a single digit after the title is a sequel number, as in `Title 2 [1080p]`, far more
+// often than an episode nobody padded
+static EP_TRAILING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s(\d{2,3})(?:v(\d))?$").expect("valid regex"));
+static EP_BRACKET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:第|EP?)?\s*(\d{1,4})\s*[话話集]?(?:v(\d))?(?:\s*(?:end|fin|完))?$")
+        .expect("valid regex")
+});
+static EP_BRACKET_RANGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:第)?(\d{1,4})\s*[-~～]\s*(\d{1,4})\s*[话話集]?(?:\s*(?:tv|end|fin|完|合集|全集|精校|\+.*))*$",
+    )
+        .expect("valid regex")
+});
+
+static SEASON_SUFFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\s+(?:S(\d{1,2})|Season\s*(\d{1,2})|(\d{1,2})(?:st|nd|rd|th)\s+Season)$")
+        .expect("valid regex")
+});
+static SEASON_CN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"第([一二三四五六七八九十\d]{1,3})[季期]").expect("valid regex"));
+
+static RESOLUTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(\d{3,4})[pi]$").expect("valid regex"));
+static DIMENSIONS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^\d{3,4}[x×](\d{3,4})$").expect("valid regex"));
+static CRC: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[0-9A-Fa-f]{8}$").expect("valid regex"));
+static VERSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^v(\d)$").expect("valid regex"));
+
+/// Characters splitting a tag group like `WebRip 1080p HEVC-10bit AAC` into words.
+const WORD_SEPARATORS: &[char] = &[' ', '_', '.', '+', ',', '&', '/', '|', '，', '、'];
+
+/// Streaming platforms fansubs tag their web rips with.
+const WEB_PLATFORMS: &[&str] = &[
+    "baha",
+    "cr",
+    "b-global",
+    "bilibili",
+    "abema",
+    "netflix",
+    "nf",
+    "amzn",
+    "dsnp",
+    "hidive",
+    "adn",
+    "viutv",
+    "iqiyi",
+    "friday",
+    "youku",
+    "tencent",
+    "crunchyroll",
+];
+
+/// What a single word inside a tag group stands for.
+enum Tag {
+    Resolution(u32),
+    Source(&'static str),
+    VideoCodec(&'static str),
+    AudioCodec(&'static str),
+    Languages(Vec<&'static str>),
+    Version(u32),
+}
+
+impl ReleaseInfo {
+    pub fn parse(name: &str) -> Self {
+        let mut info = Self::default();
+
+        let name = EXTENSION.replace(name.trim(), "");
+        let name = DOTTED_CODEC.replace_all(&name, "H$1");
+        // scene style names separate words with dots instead of spaces
+        let name = if !name.contains(' ') && name.matches('.').count() >= 2 {
+            name.replace('.', " ")
+        } else {
+            name.into_owned()
+        };
+
+        let (free_text, brackets) = Self::split_brackets(&name);
+        let title_candidates = info.parse_brackets(brackets);
+
+        // scene style names end with "-GROUP" instead of starting with "[Group]"
+        let mut free_text = free_text;
+        if info.group.is_none()
+            && !name.contains('[')
+            && let Some(cap) = SCENE_GROUP
+                .captures(&free_text)
+                .filter(|cap| !cap[1].chars().all(|c| c.is_ascii_digit()))
+        {
+            info.group = Some(cap[1].to_owned());
+            let start = cap.get(0).expect("group 0 always exists").start();
+            free_text.truncate(start);
+        }
+
+        let title = info.parse_free_text(&free_text);
+        info.title = if title.is_empty() {
+            title_candidates.into_iter().next()
+        } else {
+            Some(title)
+        };
+
+        info.languages.sort_unstable();
+        info.languages.dedup();
+        info
+    }
+
+    /// Splits a name into its free text and the contents of its brackets, each with its
+    /// position and whether it is a decorative `★...★` one.
+    fn split_brackets(name: &str) -> (String, Vec<(usize, String, bool)>) {
+        let mut free_text = String::new();
+        let mut brackets = Vec::new();
+        let mut last_end = 0;
+        for cap in BRACKET.captures_iter(name) {
+            let whole = cap.get(0).expect("group 0 always exists");
+            free_text.push_str(&name[last_end..whole.start()]);
+            free_text.push(' ');
+            let decorative = whole.as_str().starts_with('★');
+            brackets.push((whole.start(), cap[1].trim().to_owned(), decorative));
+            last_end = whole.end();
+        }
+        free_text.push_str(&name[last_end..]);
+        let free_text = free_text.split_whitespace().collect::<Vec<_>>().join(" ");
+        (free_text, brackets)
+    }
+
+    /// Reads group, episodes, CRC and tags from brackets and returns the brackets that may
+    /// hold the title, as in `[Group][标题][05][1080p]`.
+    fn parse_brackets(&mut self, brackets: Vec<(usize, String, bool)>) -> Vec<String> {
+        let mut brackets = brackets.into_iter().peekable();
+        if let Some(&(0, ref group, false)) = brackets.peek()
+            && !group.is_empty()
+            && Self::bracket_episodes(group).is_none()
+        {
+            self.group = Some(group.clone());
+            brackets.next();
+        }
+
+        let mut title_candidates = Vec::new();
+        for (_, content, decorative) in brackets {
+            if content.is_empty() {
+                continue;
+            }
+            if self.episodes.is_none()
+                && let Some((episodes, version)) = Self::bracket_episodes(&content)
+            {
+                self.episodes = Some(episodes);
+                self.version = self.version.or(version);
+                continue;
+            }
+            if self.crc.is_none()
+                && CRC.is_match(&content)
+                && content.chars().any(|c| c.is_ascii_alphabetic())
+            {
+                self.crc = Some(content.to_uppercase());
+                continue;
+            }
+            if let Some(season) = Self::cn_season(&content) {
+                self.season = self.season.or(Some(season));
+            }
+            let mut tagged = false;
+            for word in content.split(WORD_SEPARATORS).filter(|w| !w.is_empty()) {
+                if let Some(tag) = Self::classify(word) {
+                    self.apply(tag);
+                    tagged = true;
+                }
+            }
+            if !tagged && !decorative && !content.contains("新番") {
+                title_candidates.push(content);
+            }
+        }
+        title_candidates
+    }
+
+    /// Reads episodes, season and tags from the free text, e.g. `Title S2 - 05v2` or
+    /// `Title S01E05 1080p WEB-DL`, and returns the title part of it.
+    fn parse_free_text(&mut self, free_text: &str) -> String {
+        let title_end = if let Some((start, end)) = self.free_text_episodes(free_text) {
+            for word in free_text[end..].split(WORD_SEPARATORS) {
+                if let Some(tag) = Self::classify(word) {
+                    self.apply(tag);
+                }
+            }
+            start
+        } else {
+            // without an episode marker, e.g. a scene style movie, the first tag ends the title
+            let mut title_end = None;
+            let mut offset = 0;
+            for word in free_text.split(' ') {
+                if let Some(tag) = Self::classify(word) {
+                    title_end = title_end.or(Some(offset));
+                    self.apply(tag);
+                }
+                offset += word.len() + 1;
+            }
+            title_end.unwrap_or(free_text.len())
+        };
+
+        let mut title = free_text[..title_end]
+            .trim_end_matches(|c: char| c.is_whitespace() || matches!(c, '-' | '_' | '|'))
+            .trim()
+            .to_owned();
+        if let Some(cap) = SEASON_SUFFIX.captures(&title) {
+            self.season = self
+                .season
+                .or_else(|| cap.iter().skip(1).flatten().next()?.as_str().parse().ok());
+            let start = cap.get(0).expect("group 0 always exists").start();
+            title.truncate(start);
+        }
+        if self.season.is_none() {
+            self.season = Self::cn_season(&title);
+        }
+        title
+    }
+
+    /// Whether this release contains the given episode, batches included.
+    pub fn contains_episode(&self, ep: i32) -> bool {
+        self.episodes.is_some_and(|e| e.contains(ep))
+    }
+
+    fn apply(&mut self, tag: Tag) {
+        match tag {
+            Tag::Resolution(r) => self.resolution = self.resolution.or(Some(r)),
+            Tag::Source(s) => self.source = self.source.take().or_else(|| Some(s.to_owned())),
+            Tag::VideoCodec(c) => {
+                self.video_codec = self.video_codec.take().or_else(|| Some(c.to_owned()));
+            }
+            Tag::AudioCodec(c) => {
+                self.audio_codec = self.audio_codec.take().or_else(|| Some(c.to_owned()));
+            }
+            Tag::Languages(langs) => self
+                .languages
+                .extend(langs.into_iter().map(ToOwned::to_owned)),
+            Tag::Version(v) => self.version = self.version.or(Some(v)),
+        }
+    }
+
+    /// Finds the episode marker in the free text and returns its byte span.
+    fn free_text_episodes(&mut self, text: &str) -> Option<(usize, usize)> {
+        let span = |cap: &Captures| {
+            let m = cap.get(0).expect("group 0 always exists");
+            (m.start(), m.end())
+        };
+        let num = |cap: &Captures, i: usize| cap.get(i)?.as_str().parse::<i32>().ok();
+        let version = |cap: &Captures, i: usize| cap.get(i)?.as_str().parse::<u32>().ok();
+
+        if let Some(cap) = EP_SEASON.captures(text) {
+            self.season = self.season.or_else(|| cap[1].parse().ok());
+            let start = num(&cap, 2)?;
+            self.set_episodes(start, num(&cap, 3), version(&cap, 4));
+            return Some(span(&cap));
+        }
+        if let Some(cap) = EP_CN_RANGE.captures(text) {
+            self.set_episodes(num(&cap, 1)?, num(&cap, 2), None);
+            return Some(span(&cap));
+        }
+        for re in [&EP_CN, &EP_DASH, &EP_PREFIXED] {
+            if let Some(cap) = re.captures(text) {
+                // EP_DASH also captures a range end, the others only a version
+                let (end, ver) = if cap.len() == 4 {
+                    (num(&cap, 2), version(&cap, 3))
+                } else {
+                    (None, version(&cap, 2))
+                };
+                self.set_episodes(num(&cap, 1)?, end, ver);
+                return Some(span(&cap));
+            }
+        }
+        if self.episodes.is_none()
+            && let Some(cap) = EP_TRAILING.captures(text)
+        {
+            self.set_episodes(num(&cap, 1)?, None, version(&cap, 2));
+            return Some(span(&cap));
+        }
+        None
+    }
+
+    fn set_episodes(&mut self, start: i32, end: Option<i32>, version: Option<u32>) {
+        if self.episodes.is_none() {
+            self.episodes = Some(EpisodeRange {
+                start,
+                end: end.filter(|&e| e > start).unwrap_or(start),
+            });
+        }
+        self.version = self.version.or(version);
+    }
+
+    /// Episode numbers given as a bracket of their own: `[05]`, `[05v2]`, `[第05话]`, `[01-12]`.
+    fn bracket_episodes(content: &str) -> Option<(EpisodeRange, Option<u32>)> {
+        if let Some(cap) = EP_BRACKET_RANGE.captures(content) {
+            let start = cap[1].parse().ok()?;
+            let end = cap[2].parse::<i32>().ok().filter(|&e| e > start)?;
+            return Some((EpisodeRange { start, end }, None));
+        }
+        let cap = EP_BRACKET.captures(content)?;
+        let ep = cap[1].parse::<i32>().ok()?;
+        // four digit numbers in brackets are years far more often than episodes
+        if (1900..2100).contains(&ep) {
+            return None;
+        }
+        let version = cap.get(2).and_then(|m| m.as_str().parse().ok());
+        Some((EpisodeRange::single(ep), version))
+    }
+
+    fn cn_season(text: &str) -> Option<u32> {
+        let cap = SEASON_CN.captures(text)?;
+        let s = &cap[1];
+        s.parse().ok().or_else(|| {
+            const DIGITS: &str = "一二三四五六七八九";
+            let n = match *s.chars().collect::<Vec<_>>().as_slice() {
+                ['十'] => 10,
+                [d] => DIGITS.chars().position(|c| c == d)? + 1,
+                ['十', d] => DIGITS.chars().position(|c| c == d)? + 11,
+                _ => return None,
+            };
+            u32::try_from(n).ok()
+        })
+    }
+
+    fn classify(word: &str) -> Option<Tag> {
+        let lower = word.to_lowercase();
+        let lower = lower.as_str();
+
+        if let Some(cap) = RESOLUTION
+            .captures(lower)
+            .or_else(|| DIMENSIONS.captures(lower))
+        {
+            return cap[1].parse().ok().map(Tag::Resolution);
+        }
+        if matches!(lower, "4k" | "uhd") {
+            return Some(Tag::Resolution(2160));
+        }
+        if let Some(cap) = VERSION.captures(lower) {
+            return cap[1].parse().ok().map(Tag::Version);
+        }
+
+        let source = match lower {
+            "bd" | "bdrip" | "bdremux" | "bluray" | "blu-ray" | "bdmv" | "bd-box" | "bdbox" => {
+                Some("BD")
+            }
+            "web" | "web-dl" | "webdl" | "web-rip" | "webrip" => Some("WEB"),
+            "tv" | "tvrip" | "hdtv" | "hdtvrip" => Some("TV"),
+            "dvd" | "dvdrip" => Some("DVD"),
+            _ if WEB_PLATFORMS.contains(&lower) => Some("WEB"),
+            _ => None,
+        };
+        if let Some(source) = source {
+            return Some(Tag::Source(source));
+        }
+
+        let video_codec = if ["x265", "h265", "hevc"]
+            .iter()
+            .any(|p| lower.starts_with(p))
+        {
+            Some("HEVC")
+        } else if ["x264", "h264", "avc"].iter().any(|p| lower.starts_with(p)) {
+            Some("AVC")
+        } else if lower.starts_with("av1") {
+            Some("AV1")
+        } else if lower.starts_with("vp9") {
+            Some("VP9")
+        } else {
+            None
+        };
+        if let Some(codec) = video_codec {
+            return Some(Tag::VideoCodec(codec));
+        }
+
+        let audio_codec = if lower.starts_with("aac") {
+            Some("AAC")
+        } else if lower.starts_with("flac") {
+            Some("FLAC")
+        } else if lower.starts_with("opus") {
+            Some("Opus")
+        } else if ["eac3", "e-ac-3", "ddp", "dd+"]
+            .iter()
+            .any(|p| lower.starts_with(p))
+        {
+            Some("EAC3")
+        } else if ["ac3", "ac-3"].iter().any(|p| lower.starts_with(p)) {
+            Some("AC3")
+        } else if lower.starts_with("dts") {
+            Some("DTS")
+        } else if lower.starts_with("truehd") {
+            Some("TrueHD")
+        } else if lower.starts_with("mp3") {
+            Some("MP3")
+        } else {
+            None
+        };
+        if let Some(codec) = audio_codec {
+            return Some(Tag::AudioCodec(codec));
+        }
+
+        Self::languages(word, lower).map(Tag::Languages)
+    }
+
+    fn languages(word: &str, lower: &str) -> Option<Vec<&'static str>> {
+        let langs = match lower {
+            "chs" | "sc" | "gb" => vec!["zh-Hans"],
+            "cht" | "tc" | "big5" => vec!["zh-Hant"],
+            "jpsc" | "jpchs" => vec!["ja", "zh-Hans"],
+            "jptc" | "jpcht" => vec!["ja", "zh-Hant"],
+            "jp" | "jpn" | "jap" => vec!["ja"],
+            "eng" | "en" | "english" => vec!["en"],
+            "multi-sub" | "multi-subs" | "multisub" | "multisubs" | "multi" => vec!["multi"],
+            _ => {
+                // chinese tags like 简日双语, 简繁内封字幕 or 繁体
+                const MARKERS: &[&str] = &[
+                    "简", "繁", "双语", "字幕", "内封", "内嵌", "外挂", "中文", "中字", "日文",
+                    "日语",
+                ];
+                if word.chars().count() > 8 || !MARKERS.iter().any(|m| word.contains(m)) {
+                    return None;
+                }
+                let mut langs = Vec::new();
+                if word.contains('简') {
+                    langs.push("zh-Hans");
+                }
+                if word.contains('繁') {
+                    langs.push("zh-Hant");
+                }
+                if langs.is_empty() && word.contains('中') {
+                    langs.push("zh");
+                }
+                if word.contains('日') {
+                    langs.push("ja");
+                }
+                if word.contains('英') {
+                    langs.push("en");
+                }
+                langs
+            }
+        };
+        Some(langs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EpisodeRange, ReleaseInfo};
+
+    /// Name, group, episodes, resolution, video and audio codec.
+    type Case = (
+        &'static str,
+        Option<&'static str>,
+        Option<(i32, i32)>,
+        Option<u32>,
+        Option<&'static str>,
+        Option<&'static str>,
+    );
+
+    const CASES: &[Case] = &[
+        // nyaa
+        (
+            "[SubsPlease] Sousou no Frieren - 05 (1080p) [4A2D5F0E].mkv",
+            Some("SubsPlease"),
+            Some((5, 5)),
+            Some(1080),
+            None,
+            None,
+        ),
+        (
+            "[SubsPlease] Sousou no Frieren - 05v2 (720p) [0B1C2D3E].mkv",
+            Some("SubsPlease"),
+            Some((5, 5)),
+            Some(720),
+            None,
+            None,
+        ),
+        (
+            "[SubsPlease] Jujutsu Kaisen S2 - 29 (1080p) [9D8E7F6A].mkv",
+            Some("SubsPlease"),
+            Some((29, 29)),
+            Some(1080),
+            None,
+            None,
+        ),
+        (
+            "[SubsPlease] Sousou no Frieren (01-28) (1080p) [Batch]",
+            Some("SubsPlease"),
+            Some((1, 28)),
+            Some(1080),
+            None,
+            None,
+        ),
+        (
+            "[Erai-raws] Sousou no Frieren - 05 [1080p][Multiple Subtitle][ENG][POR-BR]",
+            Some("Erai-raws"),
+            Some((5, 5)),
+            Some(1080),
+            None,
+            None,
+        ),
+        (
+            "[Erai-raws] Spy x Family Season 2 - 05 [720p][Multiple Subtitle]",
+            Some("Erai-raws"),
+            Some((5, 5)),
+            Some(720),
+            None,
+            None,
+        ),
+        (
+            "[ASW] Sousou no Frieren - 05 [1080p HEVC x265 10Bit][AAC]",
+            Some("ASW"),
+            Some((5, 5)),
+            Some(1080),
+            Some("HEVC"),
+            Some("AAC"),
+        ),
+        (
+            "[Judas] Kimetsu no Yaiba (Season 3) [1080p][HEVC x265 10bit][Multi-Subs] (Batch)",
+            Some("Judas"),
+            None,
+            Some(1080),
+            Some("HEVC"),
+            None,
+        ),
+        (
+            "[Judas] Oshi no Ko 2 [1080p][HEVC x265 10bit][Multi-Subs] (Batch)",
+            Some("Judas"),
+            None,
+            Some(1080),
+            Some("HEVC"),
+            None,
+        ),
+        (
+            "[Moozzi2] Sousou no Frieren [ 01-28 ] (BD 1920x1080 x265-10Bit Flac)",
+            Some("Moozzi2"),
+            Some((1, 28)),
+            Some(1080),
+            Some("HEVC"),
+            Some("FLAC"),
+        ),
+        (
+            "[GJ.Y] Kusuriya no Hitorigoto - 05 (CR 1920x1080 AVC AAC MKV)",
+            Some("GJ.Y"),
+            Some((5, 5)),
+            Some(1080),
+            Some("AVC"),
+            Some("AAC"),
+        ),
+        (
+            "[Lilith-Raws] Kusuriya no Hitorigoto - 05 [Baha][WEB-DL][1080p][AVC AAC][CHT][MP4]",
+            Some("Lilith-Raws"),
+            Some((5, 5)),
+            Some(1080),
+            Some("AVC"),
+            Some("AAC"),
+        ),
+        (
+            "[Nekomoe kissaten&LoliHouse] Kusuriya no Hitorigoto - 05v2 [WebRip 1080p HEVC-10bit AAC ASSx2].mkv",
+            Some("Nekomoe kissaten&LoliHouse"),
+            Some((5, 5)),
+            Some(1080),
+            Some("HEVC"),
+            Some("AAC"),
+        ),
+        (
+            "Sousou.no.Frieren.S01E05.1080p.WEB.H264-VARYG",
+            Some("VARYG"),
+            Some((5, 5)),
+            Some(1080),
+            Some("AVC"),
+            None,
+        ),
+        (
+            "Frieren.Beyond.Journeys.End.S01E05.Phantoms.of.the.Dead.1080p.CR.WEB-DL.AAC2.0.H.264-VARYG.mkv",
+            Some("VARYG"),
+            Some((5, 5)),
+            Some(1080),
+            Some("AVC"),
+            Some("AAC"),
+        ),
+        (
+            "[ANi] 葬送的芙莉蓮 - 05 [1080P][Baha][WEB-DL][AAC AVC][CHT].mp4",
+            Some("ANi"),
+            Some((5, 5)),
+            Some(1080),
+            Some("AVC"),
+            Some("AAC"),
+        ),
+        // dmhy
+        (
+            "[LoliHouse] 葬送的芙莉莲 / Sousou no Frieren - 05 [WebRip 1080p HEVC-10bit AAC][简繁内封字幕]",
+            Some("LoliHouse"),
+            Some((5, 5)),
+            Some(1080),
+            Some("HEVC"),
+            Some("AAC"),
+        ),
+        (
+            "[桜都字幕组] 葬送的芙莉莲 / Sousou no Frieren [05][1080p][简体内嵌]",
+            Some("桜都字幕组"),
+            Some((5, 5)),
+            Some(1080),
+            None,
+            None,
+        ),
+        (
+            "[天月搬運組] 葬送的芙莉蓮 / Sousou no Frieren - 05 [1080P][簡繁日外掛]",
+            Some("天月搬運組"),
+            Some((5, 5)),
+            Some(1080),
+            None,
+            None,
+        ),
+        (
+            "[北宇治字幕组] 葬送的芙莉莲 / Sousou no Frieren [01-28][WebRip][HEVC_AAC][简繁日内封]",
+            Some("北宇治字幕组"),
+            Some((1, 28)),
+            None,
+            Some("HEVC"),
+            Some("AAC"),
+        ),
+        (
+            "[DBD-Raws][葬送的芙莉莲/Sousou no Frieren][01-28TV全集+SP][1080P][BDRip][HEVC-10bit][简繁日双语外挂][FLAC][MKV]",
+            Some("DBD-Raws"),
+            Some((1, 28)),
+            Some(1080),
+            Some("HEVC"),
+            Some("FLAC"),
+        ),
+        (
+            "[云光字幕组] 葬送的芙莉莲 第05话 [简体双语][1080p]",
+            Some("云光字幕组"),
+            Some((5, 5)),
+            Some(1080),
+            None,
+            None,
+        ),
+        (
+            "[豌豆字幕组&风之圣殿字幕组&LoliHouse] 间谍过家家 / SPY×FAMILY 第二季 - 05 [WebRip 1080p HEVC-10bit AAC][简繁外挂字幕]",
+            Some("豌豆字幕组&风之圣殿字幕组&LoliHouse"),
+            Some((5, 5)),
+            Some(1080),
+            Some("HEVC"),
+            Some("AAC"),
+        ),
+        (
+            "【幻樱字幕组】【10月新番】【葬送的芙莉莲 Sousou no Frieren】【05】【GB_MP4】【1920X1080】",
+            Some("幻樱字幕组"),
+            Some((5, 5)),
+            Some(1080),
+            None,
+            None,
+        ),
+        (
+            "[爱恋字幕社][10月新番][葬送的芙莉莲][Sousou no Frieren][05][1080p][MP4][GB][简中]",
+            Some("爱恋字幕社"),
+            Some((5, 5)),
+            Some(1080),
+            None,
+            None,
+        ),
+        // mikan
+        (
+            "【喵萌奶茶屋】★10月新番★[葬送的芙莉莲 / Sousou no Frieren][05][1080p][简日双语][招募翻译]",
+            Some("喵萌奶茶屋"),
+            Some((5, 5)),
+            Some(1080),
+            None,
+            None,
+        ),
+        (
+            "[Nekomoe kissaten][Sousou no Frieren][05][1080p][JPSC].mp4",
+            Some("Nekomoe kissaten"),
+            Some((5, 5)),
+            Some(1080),
+            None,
+            None,
+        ),
+        (
+            "[SweetSub][葬送的芙莉莲][Sousou no Frieren][05][WebRip][1080P][AVC 8bit][简日双语]",
+            Some("SweetSub"),
+            Some((5, 5)),
+            Some(1080),
+            Some("AVC"),
+            None,
+        ),
+        (
+            "[Sakurato] Sousou no Frieren [05][AVC-8bit 1080p AAC][CHS]",
+            Some("Sakurato"),
+            Some((5, 5)),
+            Some(1080),
+            Some("AVC"),
+            Some("AAC"),
+        ),
+        (
+            "[织梦字幕组][葬送的芙莉莲 Sousou no Frieren][05集][1080P][AVC][简日双语]",
+            Some("织梦字幕组"),
+            Some((5, 5)),
+            Some(1080),
+            Some("AVC"),
+            None,
+        ),
+        (
+            "[ANi] Sousou no Frieren - 葬送的芙莉蓮 - 05 [1080P][Baha][WEB-DL][AAC AVC][CHT].mp4",
+            Some("ANi"),
+            Some((5, 5)),
+            Some(1080),
+            Some("AVC"),
+            Some("AAC"),
+        ),
+    ];
+
+    #[test]
+    fn parses_corpus() {
+        for &(name, group, episodes, resolution, video_codec, audio_codec) in CASES {
+            let info = ReleaseInfo::parse(name);
+            assert_eq!(info.group.as_deref(), group, "group of {name}");
+            assert_eq!(
+                info.episodes,
+                episodes.map(|(start, end)| EpisodeRange { start, end }),
+                "episodes of {name}"
+            );
+            assert_eq!(info.resolution, resolution, "resolution of {name}");
+            assert_eq!(
+                info.video_codec.as_deref(),
+                video_codec,
+                "video codec of {name}"
+            );
+            assert_eq!(
+                info.audio_codec.as_deref(),
+                audio_codec,
+                "audio codec of {name}"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_titles_and_seasons() {
+        let cases = [
+            (
+                "[SubsPlease] Sousou no Frieren - 05 (1080p) [4A2D5F0E].mkv",
+                "Sousou no Frieren",
+                None,
+            ),
+            (
+                "[Judas] Oshi no Ko 2 [1080p][HEVC x265 10bit][Multi-Subs] (Batch)",
+                "Oshi no Ko 2",
+                None,
+            ),
+            (
+                "[Erai-raws] Spy x Family Season 2 - 05 [720p][Multiple Subtitle]",
+                "Spy x Family",
+                Some(2),
+            ),
+            (
+                "[SubsPlease] Jujutsu Kaisen S2 - 29 (1080p) [9D8E7F6A].mkv",
+                "Jujutsu Kaisen",
+                Some(2),
+            ),
+            (
+                "Sousou.no.Frieren.S01E05.1080p.WEB.H264-VARYG",
+                "Sousou no Frieren",
+                Some(1),
+            ),
+            (
+                "[豌豆字幕组&风之圣殿字幕组&LoliHouse] 间谍过家家 / SPY×FAMILY 第二季 - 05 [WebRip 1080p HEVC-10bit AAC][简繁外挂字幕]",
+                "间谍过家家 / SPY×FAMILY 第二季",
+                Some(2),
+            ),
+            (
+                "【喵萌奶茶屋】★10月新番★[葬送的芙莉莲 / Sousou no Frieren][05][1080p][简日双语][招募翻译]",
+                "葬送的芙莉莲 / Sousou no Frieren",
+                None,
+            ),
+        ];
+        for (name, title, season) in cases {
+            let info = ReleaseInfo::parse(name);
+            assert_eq!(info.title.as_deref(), Some(title), "title of {name}");
+            assert_eq!(info.season, season, "season of {name}");
+        }
+    }
+
+    #[test]
+    fn parses_sources_languages_versions_and_crcs() {
+        let info = ReleaseInfo::parse(
+            "[LoliHouse] 葬送的芙莉莲 / Sousou no Frieren - 05 [WebRip 1080p HEVC-10bit AAC][简繁内封字幕]",
+        );
+        assert_eq!(info.source.as_deref(), Some("WEB"));
+        assert_eq!(info.languages, ["zh-Hans", "zh-Hant"]);
+
+        let info = ReleaseInfo::parse(
+            "[DBD-Raws][葬送的芙莉莲/Sousou no Frieren][01-28TV全集+SP][1080P][BDRip][HEVC-10bit][简繁日双语外挂][FLAC][MKV]",
+        );
+        assert_eq!(info.source.as_deref(), Some("BD"));
+        assert_eq!(info.languages, ["ja", "zh-Hans", "zh-Hant"]);
+
+        let info = ReleaseInfo::parse("[Nekomoe kissaten][Sousou no Frieren][05][1080p][JPSC].mp4");
+        assert_eq!(info.languages, ["ja", "zh-Hans"]);
+
+        let info =
+            ReleaseInfo::parse("[ANi] 葬送的芙莉蓮 - 05 [1080P][Baha][WEB-DL][AAC AVC][CHT].mp4");
+        assert_eq!(info.source.as_deref(), Some("WEB"));
+        assert_eq!(info.languages, ["zh-Hant"]);
+
+        let info =
+            ReleaseInfo::parse("[SubsPlease] Sousou no Frieren - 05v2 (720p) [0b1c2d3e].mkv");
+        assert_eq!(info.version, Some(2));
+        assert_eq!(info.crc.as_deref(), Some("0B1C2D3E"));
+
+        let info =
+            ReleaseInfo::parse("[Sakurato] Sousou no Frieren [05v2][AVC-8bit 1080p AAC][CHS]");
+        assert_eq!(info.version, Some(2));
+        assert_eq!(info.crc, None);
+    }
+
+    #[test]
+    fn matches_batch_episodes() {
+        let info = ReleaseInfo::parse("[SubsPlease] Sousou no Frieren (01-28) (1080p) [Batch]");
+        assert!(info.contains_episode(1));
+        assert!(info.contains_episode(28));
+        assert!(!info.contains_episode(29));
+
+        let info =
+            ReleaseInfo::parse("[Judas] Oshi no Ko 2 [1080p][HEVC x265 10bit][Multi-Subs] (Batch)");
+        assert!(!info.contains_episode(2));
+    }
+}
@@ -0,0 +1,169 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+use crate::{
+    error::{KisaraError, KisaraResult},
+    events::{Event, RelocationProgress},
+};
+
+/// Folders of the download folder that are not torrents but move along with them.
+const APP_FOLDERS: [&str; 2] = ["session", "subtitles"];
+
+/// Moves the files of stopped torrents, given by info hash with their paths inside the
+/// torrent, and the session from `old` to `new`. The output folders in the session's
+/// database are rewritten to match. On failure, what was moved is moved back.
+pub fn move_downloads(
+    old: &Path,
+    new: &Path,
+    torrents: &[(String, Vec<PathBuf>)],
+    app: Option<&AppHandle>,
+) -> KisaraResult<()> {
+    let db_path = old.join("session").join("session.json");
+    let mut db = if db_path.exists() {
+        serde_json::from_slice::<Value>(&fs::read(&db_path)?)?
+    } else {
+        Value::Null
+    };
+    let output_folders = db
+        .get("torrents")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|torrents| torrents.values())
+        .filter_map(|torrent| {
+            Some((
+                torrent.get("info_hash")?.as_str()?.to_owned(),
+                PathBuf::from(torrent.get("output_folder")?.as_str()?),
+            ))
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut plan = Vec::new();
+    for torrent in torrents {
+        let folder = output_folders.get(&torrent.0).map_or(old, PathBuf::as_path);
+        // torrents kept outside the download folder stay where they are
+        let Ok(relative) = folder.strip_prefix(old) else {
+            continue;
+        };
+        for file in &torrent.1 {
+            let from = folder.join(file);
+            if from.exists() {
+                plan.push((from, new.join(relative).join(file)));
+            }
+        }
+    }
+    for folder in APP_FOLDERS {
+        for from in files_in(&old.join(folder))? {
+            let relative = from.strip_prefix(old).unwrap_or(&from).to_path_buf();
+            plan.push((from, new.join(relative)));
+        }
+    }
+
+    let total_bytes = plan
+        .iter()
+        .map(|entry| fs::metadata(&entry.0).map_or(0, |metadata| metadata.len()))
+        .sum::<u64>();
+    info!(files = plan.len(), total_bytes, "Moving downloads");
+    let mut moved = Vec::new();
+    let mut moved_bytes = 0;
+    for (from, to) in plan {
+        let len = fs::metadata(&from).map_or(0, |metadata| metadata.len());
+        if let Err(e) = move_file(&from, &to) {
+            warn!(error = %e, ?from, ?to, "Failed to move file, moving back");
+            move_back(&moved);
+            return Err(e.into());
+        }
+        moved.push((from, to));
+        moved_bytes += len;
+        if let Some(app) = app
+            && let Err(e) = RelocationProgress::new(moved_bytes, total_bytes).emit(app)
+        {
+            warn!(error = %e, "Failed to report relocation progress");
+        }
+    }
+
+    if let Some(torrents) = db.get_mut("torrents").and_then(Value::as_object_mut) {
+        for torrent in torrents.values_mut() {
+            let folder = torrent
+                .get("output_folder")
+                .and_then(Value::as_str)
+                .map(PathBuf::from);
+            if let Some(folder) = folder
+                && let Ok(relative) = folder.strip_prefix(old)
+            {
+                torrent["output_folder"] = Value::from(new.join(relative).to_string_lossy());
+            }
+        }
+        let written = serde_json::to_vec(&db)
+            .map_err(KisaraError::from)
+            .and_then(|db| {
+                fs::write(new.join("session").join("session.json"), db).map_err(KisaraError::from)
+            });
+        if let Err(e) = written {
+            move_back(&moved);
+            return Err(e);
+        }
+    }
+
+    for entry in &moved {
+        remove_empty_parents(&entry.0, old);
+    }
+    Ok(())
+}
+
+/// Renames a file, or copies it when it goes to another file system.
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == ErrorKind::CrossesDevices => {
+            fs::copy(from, to)?;
+            fs::remove_file(from)
+        }
+        result => result,
+    }
+}
+
+fn move_back(moved: &[(PathBuf, PathBuf)]) {
+    for entry in moved.iter().rev() {
+        if let Err(e) = move_file(&entry.1, &entry.0) {
+            warn!(error = %e, from = ?entry.0, to = ?entry.1, "Failed to move file back");
+        }
+    }
+}
+
+fn files_in(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(files_in(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Removes the folders a moved file leaves empty, up to `root`.
+fn remove_empty_parents(file: &Path, root: &Path) {
+    let mut dir = file.parent();
+    while let Some(current) = dir
+        && current.starts_with(root)
+        && current != root
+        && fs::remove_dir(current).is_ok()
+    {
+        dir = current.parent();
+    }
+}
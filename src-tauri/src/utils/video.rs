@@ -1,7 +1,18 @@
+use std::path::Path;
+
 use tokio::process::Command;
 
 use crate::error::{KisaraError, KisaraResult};
 
+const VIDEO_EXTENSIONS: [&str; 6] = ["mkv", "mp4", "webm", "avi", "m4v", "ts"];
+
+/// Whether a file is a video, judging by its extension.
+pub fn is_video_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
 pub async fn get_video_duration(path: &str) -> KisaraResult<f64> {
     let ffprobe = std::env::current_exe()?
         .parent()
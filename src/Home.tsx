@@ -1,12 +1,18 @@
 import CloseAppBar from "@/components/CloseAppBar";
 import KisaraSidebar from "@/components/KisaraSidebar";
 import { useCurrentTitle, useDownloadingNum } from "@/states";
-import { AppShell, Burger, ScrollArea } from "@mantine/core";
+import { Alert, AppShell, Burger, ScrollArea } from "@mantine/core";
 import { Outlet } from "react-router";
 import { useDisclosure } from "@mantine/hooks";
-import { useEffect } from "react";
+import { useEffect, useState } from "react";
 import { getDownloadingTorrentsNum } from "@/commands/commands";
+import type { DiskSpaceLow, TorrentProgress } from "@/commands/types";
 import { useTranslation } from "react-i18next";
+import { listen } from "@tauri-apps/api/event";
+
+function toGiB(bytes: number): string {
+    return `${(bytes / 1024 ** 3).toFixed(2)} GB`;
+}
 
 export default function Home() {
     const { t } = useTranslation();
@@ -15,21 +21,31 @@ export default function Home() {
     const { setNum } = useDownloadingNum((state) => state);
 
     const [open, { toggle }] = useDisclosure(false);
+    const [diskSpaceLow, setDiskSpaceLow] = useState<DiskSpaceLow | null>(
+        null
+    );
 
     useEffect(() => {
         getDownloadingTorrentsNum().then((v) => {
             setNum(v);
         });
-        const interval = setInterval(() => {
-            getDownloadingTorrentsNum().then((v) => {
-                setNum(v);
-            });
-        }, 1000);
+        const unlisten = listen<TorrentProgress>("torrent-progress", (e) => {
+            setNum(e.payload.downloading);
+        });
         return () => {
-            clearInterval(interval);
+            unlisten.then((f) => f());
         };
     }, [setNum]);
 
+    useEffect(() => {
+        const unlisten = listen<DiskSpaceLow>("disk-space-low", (e) => {
+            setDiskSpaceLow(e.payload);
+        });
+        return () => {
+            unlisten.then((f) => f());
+        };
+    }, []);
+
     useEffect(() => {
         const contextMenuListener = (e: MouseEvent) => {
             e.preventDefault();
@@ -79,6 +95,20 @@ export default function Home() {
             </AppShell.Navbar>
             <AppShell.Main>
                 <ScrollArea h="calc(100vh - 80px)">
+                    {diskSpaceLow && (
+                        <Alert
+                            color="red"
+                            title={t("disk_space_low")}
+                            withCloseButton
+                            onClose={() => setDiskSpaceLow(null)}
+                            className="mb-2"
+                        >
+                            {t("disk_space_low_description", {
+                                required: toGiB(diskSpaceLow.required_bytes),
+                                available: toGiB(diskSpaceLow.available_bytes),
+                            })}
+                        </Alert>
+                    )}
                     <Outlet />
                 </ScrollArea>
             </AppShell.Main>
@@ -11,6 +11,19 @@ import type {
 	DashboardSummary,
 	Config,
 	LogLevelFilter,
+	TorznabIndexerConfig,
+	RssFeedConfig,
+	RankingProfile,
+	SearchResults,
+	NyaaConfig,
+	Subscription,
+	AnimePreference,
+	BandwidthLimits,
+	BandwidthRule,
+	SeedingPolicy,
+	RetentionPolicy,
+	CleanupCandidate,
+	TorrentFile,
 } from "./types";
 
 export async function currentSeasonAnimes(): Promise<Anime[]> {
@@ -59,9 +72,29 @@ export async function getAnime(
 
 export async function initSearchTorrents(
 	epId: number,
-): Promise<Record<string, TorrentInfo[]>> {
-	return invoke<Record<string, TorrentInfo[]>>("init_search_torrents", {
+	query?: string,
+	forceRefresh?: boolean,
+): Promise<SearchResults> {
+	return invoke<SearchResults>("init_search_torrents", {
 		epId,
+		query,
+		forceRefresh,
+	});
+}
+
+export async function searchTorrentsPage(
+	epId: number,
+	source: string,
+	page: number,
+	query?: string,
+	forceRefresh?: boolean,
+): Promise<SearchResults> {
+	return invoke<SearchResults>("search_torrents_page", {
+		epId,
+		source,
+		page,
+		query,
+		forceRefresh,
 	});
 }
 
@@ -69,8 +102,93 @@ export async function getDownloadingTorrentsNum(): Promise<number> {
 	return invoke<number>("get_downloading_torrents_num");
 }
 
-export async function addTorrent(magnet: string, epId: number): Promise<void> {
-	return invoke<void>("add_torrent", { magnet, epId });
+export async function addTorrent(
+	magnet: string,
+	epId: number,
+	group: string | null,
+): Promise<void> {
+	return invoke<void>("add_torrent", { magnet, epId, group });
+}
+
+export async function addTorrentForSelection(
+	magnet: string,
+	epId: number,
+	group: string | null,
+): Promise<[string, TorrentFile[]]> {
+	return invoke<[string, TorrentFile[]]>("add_torrent_for_selection", {
+		magnet,
+		epId,
+		group,
+	});
+}
+
+export async function getTorrentFiles(
+	torrentId: string,
+): Promise<TorrentFile[]> {
+	return invoke<TorrentFile[]>("get_torrent_files", { torrentId });
+}
+
+export async function selectTorrentFiles(
+	torrentId: string,
+	files: number[],
+): Promise<void> {
+	return invoke<void>("select_torrent_files", { torrentId, files });
+}
+
+export async function replaceTorrent(
+	magnet: string,
+	epId: number,
+	deleteOld: boolean,
+): Promise<void> {
+	return invoke<void>("replace_torrent", { magnet, epId, deleteOld });
+}
+
+export async function pauseTorrent(torrentId: string): Promise<void> {
+	return invoke<void>("pause_torrent", { torrentId });
+}
+
+export async function resumeTorrent(torrentId: string): Promise<void> {
+	return invoke<void>("resume_torrent", { torrentId });
+}
+
+export async function setTorrentPriority(
+	torrentId: string,
+	priority: number,
+): Promise<void> {
+	return invoke<void>("set_torrent_priority", { torrentId, priority });
+}
+
+export async function setMaxActiveDownloads(
+	maxActiveDownloads: number,
+): Promise<Config> {
+	return invoke<Config>("set_max_active_downloads", { maxActiveDownloads });
+}
+
+export async function setDiskReserve(reserveGib: number): Promise<Config> {
+	return invoke<Config>("set_disk_reserve", { reserveGib });
+}
+
+export async function setBandwidthLimits(
+	limits: BandwidthLimits,
+	schedule: BandwidthRule[],
+): Promise<Config> {
+	return invoke<Config>("set_bandwidth_limits", { limits, schedule });
+}
+
+export async function setSeedingPolicy(
+	policy: SeedingPolicy,
+): Promise<Config> {
+	return invoke<Config>("set_seeding_policy", { policy });
+}
+
+export async function setRetentionPolicy(
+	policy: RetentionPolicy,
+): Promise<Config> {
+	return invoke<Config>("set_retention_policy", { policy });
+}
+
+export async function previewCleanup(): Promise<CleanupCandidate[]> {
+	return invoke<CleanupCandidate[]>("preview_cleanup");
 }
 
 export async function getTorrentStats(): Promise<TorrentStat[]> {
@@ -103,16 +221,25 @@ export async function openUrl(url: string): Promise<void> {
 
 export async function parseTorrentPlayInfo(
 	torrentId: string,
+	epId?: number,
 ): Promise<PlayInfo> {
-	return invoke<PlayInfo>("parse_torrent_play_info_v2", { torrentId });
+	return invoke<PlayInfo>("parse_torrent_play_info_v2", { torrentId, epId });
 }
 
 export async function setProgress(
 	epId: number,
 	progress: number,
+	duration?: number,
 ): Promise<void> {
 	const intProgress = Math.floor(progress);
-	return invoke<void>("set_progress", { epId, progress: intProgress });
+	// NaN until the video's metadata has loaded
+	const intDuration =
+		duration && Number.isFinite(duration) ? Math.floor(duration) : null;
+	return invoke<void>("set_progress", {
+		epId,
+		progress: intProgress,
+		duration: intDuration,
+	});
 }
 
 export async function fullscreenWindow(): Promise<void> {
@@ -179,6 +306,30 @@ export async function setLogLevel(level: LogLevelFilter): Promise<Config> {
 	return invoke<Config>("set_log_level", { level });
 }
 
+export async function setTorznabIndexers(
+	indexers: TorznabIndexerConfig[],
+): Promise<Config> {
+	return invoke<Config>("set_torznab_indexers", { indexers });
+}
+
+export async function setRssFeeds(feeds: RssFeedConfig[]): Promise<Config> {
+	return invoke<Config>("set_rss_feeds", { feeds });
+}
+
+export async function setRankingProfile(
+	profile: RankingProfile,
+): Promise<Config> {
+	return invoke<Config>("set_ranking_profile", { profile });
+}
+
+export async function setSearchCacheTtl(ttlSecs: number): Promise<Config> {
+	return invoke<Config>("set_search_cache_ttl", { ttlSecs });
+}
+
+export async function setNyaaConfig(nyaa: NyaaConfig): Promise<Config> {
+	return invoke<Config>("set_nyaa_config", { nyaa });
+}
+
 export async function getAirCalendar(): Promise<[Anime, Episode][][]> {
 	return invoke<[Anime, Episode][][]>("get_air_calendar");
 }
@@ -190,6 +341,47 @@ export async function setAnimeKeywords(
 	return invoke<void>("set_anime_keywords", { animeId, keywords });
 }
 
+export async function getSubscription(
+	animeId: number,
+): Promise<Subscription | null> {
+	return invoke<Subscription | null>("get_subscription", { animeId });
+}
+
+export async function setSubscription(
+	subscription: Subscription,
+): Promise<void> {
+	return invoke<void>("set_subscription", { subscription });
+}
+
+export async function removeSubscription(animeId: number): Promise<void> {
+	return invoke<void>("remove_subscription", { animeId });
+}
+
+export async function getAnimePreference(
+	animeId: number,
+): Promise<AnimePreference | null> {
+	return invoke<AnimePreference | null>("get_anime_preference", { animeId });
+}
+
+export async function setAnimePreference(
+	preference: AnimePreference,
+): Promise<void> {
+	return invoke<void>("set_anime_preference", { preference });
+}
+
+export async function getAnimeSeedingPolicy(
+	animeId: number,
+): Promise<SeedingPolicy | null> {
+	return invoke<SeedingPolicy | null>("get_anime_seeding_policy", { animeId });
+}
+
+export async function setAnimeSeedingPolicy(
+	animeId: number,
+	policy: SeedingPolicy | null,
+): Promise<void> {
+	return invoke<void>("set_anime_seeding_policy", { animeId, policy });
+}
+
 export async function getAnimeById(animeId: number): Promise<Anime> {
 	return invoke<Anime>("get_anime_by_id", { animeId });
 }
@@ -74,12 +74,55 @@ export interface Episode {
 export interface TorrentInfo {
 	name: string;
 	size?: string | null;
+	size_bytes?: number | null;
 	url?: string | null;
 	magnet: string;
 	date?: string | null;
+	/** RFC 3339, UTC */
+	timestamp?: string | null;
 	seeders?: number | null;
 	leechers?: number | null;
 	uploader?: string | null;
+	release: ReleaseInfo;
+	source: string;
+	score?: TorrentScore | null;
+}
+
+export interface SourceResult {
+	source: string;
+	torrents: number;
+	error?: string | null;
+	elapsed_ms: number;
+	cached: boolean;
+}
+
+export interface SearchResults {
+	torrents: TorrentInfo[];
+	sources: SourceResult[];
+}
+
+export interface TorrentScore {
+	value: number;
+	reasons: string[];
+}
+
+export interface EpisodeRange {
+	start: number;
+	end: number;
+}
+
+export interface ReleaseInfo {
+	group?: string | null;
+	title?: string | null;
+	episodes?: EpisodeRange | null;
+	season?: number | null;
+	resolution?: number | null;
+	source?: string | null;
+	video_codec?: string | null;
+	audio_codec?: string | null;
+	languages: string[];
+	version?: number | null;
+	crc?: string | null;
 }
 
 export interface TorrentStat {
@@ -87,6 +130,38 @@ export interface TorrentStat {
 	ep: number;
 	info: ManagedTorrentInfo;
 	torrent_id: string;
+	control: TorrentControl;
+	/** only for finished torrents */
+	seeding: TorrentSeeding | null;
+	/** a better release downloading to replace the episode's torrent */
+	upgrade: boolean;
+}
+
+export interface TorrentFile {
+	index: number;
+	/** path inside the torrent */
+	name: string;
+	size: number;
+	selected: boolean;
+}
+
+export interface TorrentSeeding {
+	info_hash: string;
+	since: string;
+	uploaded_bytes: number;
+	/** stopped by the seeding policy */
+	stopped: boolean;
+}
+
+export interface SeedingPolicy {
+	max_ratio?: number | null;
+	max_hours?: number | null;
+}
+
+export interface TorrentControl {
+	info_hash: string;
+	priority: number;
+	paused: boolean;
 }
 
 export interface ManagedTorrentInfo {
@@ -144,9 +219,47 @@ export interface AggregatePeerStats {
 	steals: number;
 }
 
+export interface TorrentProgressEntry {
+	id: string;
+	anime_name: string | null;
+	ep: number | null;
+	state: TorrentStatsState;
+	error: string | null;
+	finished: boolean;
+	progress_bytes: number;
+	uploaded_bytes: number;
+	total_bytes: number;
+	/** MiB/s */
+	download_speed: number;
+	/** MiB/s */
+	upload_speed: number;
+	eta_secs: number | null;
+}
+
+/** payload of the `torrent-progress` event, only torrents that changed are included */
+export interface TorrentProgress {
+	torrents: TorrentProgressEntry[];
+	downloading: number;
+}
+
+/** payload of the `relocation-progress` event */
+export interface RelocationProgress {
+	moved_bytes: number;
+	total_bytes: number;
+}
+
+/** payload of the `disk-space-low` event, the torrent was refused or paused */
+export interface DiskSpaceLow {
+	id: string;
+	required_bytes: number;
+	available_bytes: number;
+}
+
 export interface PlayInfo {
 	video: string;
 	subtitles: string[];
+	// served over HTTP while the video is still downloading
+	stream_url?: string;
 	ep: Episode;
 	anime: Anime;
 }
@@ -157,9 +270,44 @@ export interface DashboardSummary {
 	watch_next: [Anime, Episode][];
 }
 
+export interface BandwidthLimits {
+	upload_kib?: number;
+	download_kib?: number;
+}
+
+export interface BandwidthRule {
+	/** 0 is Monday */
+	days: number[];
+	/** HH:MM:SS */
+	start: string;
+	end: string;
+	limits: BandwidthLimits;
+}
+
+export interface RetentionPolicy {
+	enabled: boolean;
+	watched_percent: number;
+	grace_hours: number;
+	quota_gib?: number | null;
+}
+
+export interface CleanupCandidate {
+	torrent_id: string;
+	anime_name: string;
+	eps: number[];
+	size_bytes: number;
+	reason: "watched" | "quota";
+}
+
 export interface Config {
 	download_config: {
 		download_path: string;
+		max_active_downloads: number;
+		limits: BandwidthLimits;
+		bandwidth_schedule: BandwidthRule[];
+		seeding_policy: SeedingPolicy;
+		retention_policy: RetentionPolicy;
+		disk_reserve_gib: number;
 	};
 	network_config: {
 		bgm_proxy?: string;
@@ -171,6 +319,70 @@ export interface Config {
 	debug_config: {
 		log_level: LogLevelFilter;
 	};
+	search_config: SearchConfig;
+}
+
+export interface SearchConfig {
+	torznab_indexers: TorznabIndexerConfig[];
+	rss_feeds: RssFeedConfig[];
+	ranking_profile: RankingProfile;
+	cache_ttl_secs: number;
+	nyaa: NyaaConfig;
+}
+
+export type NyaaCategory =
+	| "all_anime"
+	| "english_translated"
+	| "non_english_translated"
+	| "raw";
+
+export type NyaaFilter = "no_filter" | "no_remakes" | "trusted_only";
+
+export interface NyaaConfig {
+	base_url: string;
+	category: NyaaCategory;
+	filter: NyaaFilter;
+}
+
+export interface RankingProfile {
+	preferred_groups: string[];
+	required_words: string[];
+	forbidden_words: string[];
+	preferred_resolutions: number[];
+	preferred_codecs: string[];
+	excluded_codecs: string[];
+	min_size_mib?: number | null;
+	max_size_mib?: number | null;
+	min_seeders?: number | null;
+}
+
+export interface RssFeedConfig {
+	name: string;
+	url: string;
+}
+
+export type TorznabSearchMode = "search" | "tvsearch";
+
+export interface TorznabIndexerConfig {
+	name: string;
+	url: string;
+	api_key: string;
+	categories: number[];
+	search_mode: TorznabSearchMode;
 }
 
 export type LogLevelFilter = "info" | "warn" | "error" | "debug" | "trace";
+
+export interface Subscription {
+	anime_id: number;
+	delay_hours: number;
+	retry_hours: number;
+}
+
+export interface AnimePreference {
+	anime_id: number;
+	release_group?: string | null;
+	resolution?: number | null;
+	source?: string | null;
+	lock_group: boolean;
+}
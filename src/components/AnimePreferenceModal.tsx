@@ -0,0 +1,158 @@
+import {
+    getAnimePreference,
+    getAnimeSeedingPolicy,
+    setAnimePreference,
+    setAnimeSeedingPolicy,
+} from "@/commands/commands";
+import type { AnimePreference, SeedingPolicy } from "@/commands/types";
+import {
+    Button,
+    Input,
+    Modal,
+    NumberInput,
+    Select,
+    Switch,
+} from "@mantine/core";
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+
+const resolutions = ["2160", "1080", "720", "480"];
+const releaseSources = ["BD", "WEB", "TV", "DVD"];
+
+function defaultPreference(animeId: number): AnimePreference {
+    return {
+        anime_id: animeId,
+        release_group: null,
+        resolution: null,
+        source: null,
+        lock_group: true,
+    };
+}
+
+export default function AnimePreferenceModal({
+    animeId,
+    opened,
+    onClose,
+}: {
+    animeId: number;
+    opened: boolean;
+    onClose: () => void;
+}) {
+    const { t } = useTranslation();
+
+    const [preference, setPreference] = useState<AnimePreference>(
+        defaultPreference(animeId)
+    );
+
+    // null follows the global seeding policy
+    const [seeding, setSeeding] = useState<SeedingPolicy | null>(null);
+
+    useEffect(() => {
+        if (!opened) return;
+        getAnimePreference(animeId).then((v) => {
+            setPreference(v ?? defaultPreference(animeId));
+        });
+        getAnimeSeedingPolicy(animeId).then(setSeeding);
+    }, [animeId, opened]);
+
+    function update(values: Partial<AnimePreference>) {
+        setPreference((prev) => ({ ...prev, ...values }));
+    }
+
+    // an emptied input removes the limit, partial input is ignored
+    function setSeedingLimit(
+        key: keyof SeedingPolicy,
+        value: number | string
+    ) {
+        if (value === "") {
+            setSeeding((prev) => ({ ...prev, [key]: null }));
+        } else if (typeof value === "number") {
+            setSeeding((prev) => ({ ...prev, [key]: value }));
+        }
+    }
+
+    function save() {
+        Promise.all([
+            setAnimePreference(preference),
+            setAnimeSeedingPolicy(animeId, seeding),
+        ])
+            .catch((error) => {
+                console.error("Error saving anime preference:", error);
+            })
+            .finally(onClose);
+    }
+
+    return (
+        <Modal opened={opened} onClose={onClose} title={t("anime_preference")}>
+            <div className="flex flex-col gap-3">
+                <Input.Wrapper label={t("anime_preference_group")}>
+                    <Input
+                        placeholder={t("anime_preference_any")}
+                        value={preference.release_group ?? ""}
+                        onChange={(e) =>
+                            update({
+                                release_group:
+                                    e.currentTarget.value.trim() || null,
+                            })
+                        }
+                    />
+                </Input.Wrapper>
+                <Switch
+                    label={t("anime_preference_lock_group")}
+                    description={t("anime_preference_lock_group_description")}
+                    checked={preference.lock_group}
+                    onChange={(e) =>
+                        update({ lock_group: e.currentTarget.checked })
+                    }
+                />
+                <Select
+                    label={t("anime_preference_resolution")}
+                    placeholder={t("anime_preference_any")}
+                    data={resolutions.map((r) => ({
+                        value: r,
+                        label: `${r}p`,
+                    }))}
+                    value={preference.resolution?.toString() ?? null}
+                    onChange={(v) => update({ resolution: v ? Number(v) : null })}
+                    clearable
+                />
+                <Select
+                    label={t("anime_preference_source")}
+                    placeholder={t("anime_preference_any")}
+                    data={releaseSources}
+                    value={preference.source}
+                    onChange={(v) => update({ source: v })}
+                    clearable
+                />
+                <Switch
+                    label={t("anime_preference_seeding")}
+                    description={t("anime_preference_seeding_description")}
+                    checked={seeding !== null}
+                    onChange={(e) =>
+                        setSeeding(e.currentTarget.checked ? {} : null)
+                    }
+                />
+                <NumberInput
+                    label={t("seeding_max_ratio")}
+                    placeholder={t("seeding_unlimited")}
+                    min={0}
+                    step={0.1}
+                    decimalScale={2}
+                    value={seeding?.max_ratio ?? ""}
+                    onChange={(v) => setSeedingLimit("max_ratio", v)}
+                    disabled={seeding === null}
+                />
+                <NumberInput
+                    label={t("seeding_max_hours")}
+                    placeholder={t("seeding_unlimited")}
+                    min={0}
+                    allowDecimal={false}
+                    value={seeding?.max_hours ?? ""}
+                    onChange={(v) => setSeedingLimit("max_hours", v)}
+                    disabled={seeding === null}
+                />
+                <Button onClick={save}>{t("confirm")}</Button>
+            </div>
+        </Modal>
+    );
+}
@@ -2,9 +2,17 @@ import { getAnimeById, setAnimeKeywords } from "@/commands/commands";
 import type { Anime, AnimeSearchResultItem } from "@/commands/types";
 import { ActionIcon, Button, Modal, Input, Divider } from "@mantine/core";
 import { useDisclosure } from "@mantine/hooks";
-import { PlusIcon, SquarePen, X } from "lucide-react";
+import {
+    BellRing,
+    PlusIcon,
+    SlidersHorizontal,
+    SquarePen,
+    X,
+} from "lucide-react";
 import { useEffect, useState } from "react";
 import { useTranslation } from "react-i18next";
+import AnimePreferenceModal from "./AnimePreferenceModal";
+import SubscriptionModal from "./SubscriptionModal";
 
 export default function AnimeSummary({
     anime,
@@ -15,6 +23,14 @@ export default function AnimeSummary({
         editKeywordsOpen,
         { open: openEditKeywords, close: closeEditKeywords },
     ] = useDisclosure(false);
+    const [
+        subscriptionOpen,
+        { open: openSubscription, close: closeSubscription },
+    ] = useDisclosure(false);
+    const [
+        preferenceOpen,
+        { open: openPreference, close: closePreference },
+    ] = useDisclosure(false);
 
     const [animeInfo, setAnimeInfo] = useState<Anime | undefined>(undefined);
 
@@ -92,6 +108,20 @@ export default function AnimeSummary({
                         <ActionIcon variant="subtle" onClick={openEditKeywords}>
                             <SquarePen />
                         </ActionIcon>
+                        <ActionIcon
+                            variant="subtle"
+                            onClick={openSubscription}
+                            aria-label={t("subscription")}
+                        >
+                            <BellRing />
+                        </ActionIcon>
+                        <ActionIcon
+                            variant="subtle"
+                            onClick={openPreference}
+                            aria-label={t("anime_preference")}
+                        >
+                            <SlidersHorizontal />
+                        </ActionIcon>
                     </div>
                     <p className="text-gray-700">{anime.date}</p>
                     <div>{anime.meta_tags.join(" / ")}</div>
@@ -203,6 +233,16 @@ export default function AnimeSummary({
                     </Button>
                 </div>
             </Modal>
+            <SubscriptionModal
+                animeId={anime.id}
+                opened={subscriptionOpen}
+                onClose={closeSubscription}
+            />
+            <AnimePreferenceModal
+                animeId={anime.id}
+                opened={preferenceOpen}
+                onClose={closePreference}
+            />
         </>
     );
 }
@@ -0,0 +1,197 @@
+import { setBandwidthLimits } from "@/commands/commands";
+import type { BandwidthLimits, BandwidthRule, Config } from "@/commands/types";
+import {
+    ActionIcon,
+    Button,
+    Chip,
+    Modal,
+    NumberInput,
+    TextInput,
+} from "@mantine/core";
+import { Plus, Trash2 } from "lucide-react";
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+
+function defaultRule(): BandwidthRule {
+    return {
+        days: [0, 1, 2, 3, 4],
+        start: "09:00:00",
+        end: "18:00:00",
+        limits: {},
+    };
+}
+
+// an empty input means no limit
+function toLimit(v: number | string): number | undefined {
+    return typeof v === "number" && v > 0 ? v : undefined;
+}
+
+function LimitInputs({
+    limits,
+    onChange,
+}: {
+    limits: BandwidthLimits;
+    onChange: (limits: BandwidthLimits) => void;
+}) {
+    const { t } = useTranslation();
+
+    return (
+        <div className="flex flex-row gap-2">
+            <NumberInput
+                label={t("bandwidth_download_limit")}
+                placeholder={t("bandwidth_unlimited")}
+                min={0}
+                value={limits.download_kib ?? ""}
+                onChange={(v) =>
+                    onChange({ ...limits, download_kib: toLimit(v) })
+                }
+            />
+            <NumberInput
+                label={t("bandwidth_upload_limit")}
+                placeholder={t("bandwidth_unlimited")}
+                min={0}
+                value={limits.upload_kib ?? ""}
+                onChange={(v) => onChange({ ...limits, upload_kib: toLimit(v) })}
+            />
+        </div>
+    );
+}
+
+export default function BandwidthModal({
+    config,
+    opened,
+    onClose,
+    onSaved,
+}: {
+    config: Config["download_config"];
+    opened: boolean;
+    onClose: () => void;
+    onSaved: (config: Config) => void;
+}) {
+    const { t } = useTranslation();
+
+    const [limits, setLimits] = useState<BandwidthLimits>(config.limits);
+    const [schedule, setSchedule] = useState<BandwidthRule[]>(
+        config.bandwidth_schedule
+    );
+
+    useEffect(() => {
+        if (!opened) return;
+        setLimits(config.limits);
+        setSchedule(config.bandwidth_schedule);
+    }, [config, opened]);
+
+    function updateRule(index: number, values: Partial<BandwidthRule>) {
+        setSchedule((prev) =>
+            prev.map((rule, i) => (i === index ? { ...rule, ...values } : rule))
+        );
+    }
+
+    function save() {
+        setBandwidthLimits(limits, schedule)
+            .then(onSaved)
+            .catch((error) => {
+                console.error("Error saving bandwidth limits:", error);
+            })
+            .finally(onClose);
+    }
+
+    return (
+        <Modal
+            opened={opened}
+            onClose={onClose}
+            title={t("bandwidth")}
+            size="lg"
+        >
+            <div className="flex flex-col gap-3">
+                <LimitInputs limits={limits} onChange={setLimits} />
+                <span className="font-bold">{t("bandwidth_schedule")}</span>
+                <span className="text-sm text-gray-500">
+                    {t("bandwidth_schedule_description")}
+                </span>
+                {schedule.map((rule, index) => (
+                    <div
+                        // biome-ignore lint/suspicious/noArrayIndexKey: rules have no identity besides their position
+                        key={index}
+                        className="flex flex-col gap-2 border rounded p-2"
+                    >
+                        <div className="flex flex-row items-center justify-between">
+                            <Chip.Group
+                                multiple
+                                value={rule.days.map(String)}
+                                onChange={(days) =>
+                                    updateRule(index, {
+                                        days: days
+                                            .map(Number)
+                                            .sort((a, b) => a - b),
+                                    })
+                                }
+                            >
+                                <div className="flex flex-row gap-1">
+                                    {[0, 1, 2, 3, 4, 5, 6].map((day) => (
+                                        <Chip
+                                            key={day}
+                                            value={String(day)}
+                                            size="xs"
+                                        >
+                                            {t(
+                                                `weekdays_short.${(day + 1) % 7}`
+                                            )}
+                                        </Chip>
+                                    ))}
+                                </div>
+                            </Chip.Group>
+                            <ActionIcon
+                                variant="subtle"
+                                color="red"
+                                onClick={() =>
+                                    setSchedule((prev) =>
+                                        prev.filter((_, i) => i !== index)
+                                    )
+                                }
+                            >
+                                <Trash2 />
+                            </ActionIcon>
+                        </div>
+                        <div className="flex flex-row gap-2">
+                            <TextInput
+                                type="time"
+                                label={t("bandwidth_rule_start")}
+                                value={rule.start.slice(0, 5)}
+                                onChange={(e) =>
+                                    updateRule(index, {
+                                        start: `${e.currentTarget.value}:00`,
+                                    })
+                                }
+                            />
+                            <TextInput
+                                type="time"
+                                label={t("bandwidth_rule_end")}
+                                value={rule.end.slice(0, 5)}
+                                onChange={(e) =>
+                                    updateRule(index, {
+                                        end: `${e.currentTarget.value}:00`,
+                                    })
+                                }
+                            />
+                        </div>
+                        <LimitInputs
+                            limits={rule.limits}
+                            onChange={(l) => updateRule(index, { limits: l })}
+                        />
+                    </div>
+                ))}
+                <Button
+                    variant="outline"
+                    leftSection={<Plus />}
+                    onClick={() =>
+                        setSchedule((prev) => [...prev, defaultRule()])
+                    }
+                >
+                    {t("bandwidth_add_rule")}
+                </Button>
+                <Button onClick={save}>{t("confirm")}</Button>
+            </div>
+        </Modal>
+    );
+}
@@ -0,0 +1,82 @@
+import { previewCleanup } from "@/commands/commands";
+import type { CleanupCandidate } from "@/commands/types";
+import { Loader, Modal, Table } from "@mantine/core";
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+
+function toGiB(bytes: number): string {
+    return `${(bytes / 1024 ** 3).toFixed(2)} GB`;
+}
+
+export default function CleanupPreviewModal({
+    opened,
+    onClose,
+}: {
+    opened: boolean;
+    onClose: () => void;
+}) {
+    const { t } = useTranslation();
+
+    const [candidates, setCandidates] = useState<
+        CleanupCandidate[] | undefined
+    >(undefined);
+
+    useEffect(() => {
+        if (!opened) return;
+        setCandidates(undefined);
+        previewCleanup()
+            .then(setCandidates)
+            .catch((error) => {
+                console.error("Error previewing cleanup:", error);
+                setCandidates([]);
+            });
+    }, [opened]);
+
+    const total = candidates?.reduce((sum, c) => sum + c.size_bytes, 0) ?? 0;
+
+    return (
+        <Modal
+            opened={opened}
+            onClose={onClose}
+            title={t("cleanup_preview")}
+            size="lg"
+        >
+            {candidates === undefined ? (
+                <Loader />
+            ) : candidates.length === 0 ? (
+                <p>{t("cleanup_preview_empty")}</p>
+            ) : (
+                <div className="flex flex-col gap-2">
+                    <p>
+                        {t("cleanup_preview_total", {
+                            count: candidates.length,
+                            size: toGiB(total),
+                        })}
+                    </p>
+                    <Table>
+                        <Table.Thead>
+                            <Table.Tr>
+                                <Table.Th>{t("cleanup_anime")}</Table.Th>
+                                <Table.Th>{t("cleanup_episodes")}</Table.Th>
+                                <Table.Th>{t("cleanup_size")}</Table.Th>
+                                <Table.Th>{t("cleanup_reason")}</Table.Th>
+                            </Table.Tr>
+                        </Table.Thead>
+                        <Table.Tbody>
+                            {candidates.map((c) => (
+                                <Table.Tr key={c.torrent_id}>
+                                    <Table.Td>{c.anime_name}</Table.Td>
+                                    <Table.Td>{c.eps.join(", ")}</Table.Td>
+                                    <Table.Td>{toGiB(c.size_bytes)}</Table.Td>
+                                    <Table.Td>
+                                        {t(`cleanup_reason_${c.reason}`)}
+                                    </Table.Td>
+                                </Table.Tr>
+                            ))}
+                        </Table.Tbody>
+                    </Table>
+                </div>
+            )}
+        </Modal>
+    );
+}
@@ -3,6 +3,7 @@ import type { Episode } from "@/commands/types";
 import { ActionIcon } from "@mantine/core";
 import { Magnet, TvMinimalPlay } from "lucide-react";
 import { useEffect, useMemo, useState } from "react";
+import { useTranslation } from "react-i18next";
 import { useNavigate } from "react-router";
 
 export default function EpisodeItem({
@@ -15,6 +16,7 @@ export default function EpisodeItem({
     isLastWatched?: boolean;
 }) {
     const navigate = useNavigate();
+    const { t } = useTranslation();
 
     const [torrentId, setTorrentId] = useState<string | null>(null);
 
@@ -33,7 +35,7 @@ export default function EpisodeItem({
 
     function onClickPlayOrMagnet() {
         if (torrentPresent) {
-            navigate(`/play/${torrentId}`);
+            navigate(`/play/${torrentId}?ep=${ep.id}`);
         } else {
             onClickMagnet();
         }
@@ -78,6 +80,16 @@ export default function EpisodeItem({
                 )}
             </div>
             <div className="flex flex-row gap-2 justify-end">
+                {torrentPresent && (
+                    <ActionIcon
+                        variant="subtle"
+                        size="lg"
+                        onClick={onClickMagnet}
+                        aria-label={t("torrent_replace")}
+                    >
+                        <Magnet />
+                    </ActionIcon>
+                )}
                 <ActionIcon
                     variant="outline"
                     size="lg"
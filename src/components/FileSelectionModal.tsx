@@ -0,0 +1,114 @@
+import {
+    getTorrentFiles,
+    resumeTorrent,
+    selectTorrentFiles,
+} from "@/commands/commands";
+import type { TorrentFile } from "@/commands/types";
+import { Button, Checkbox, Loader, Modal } from "@mantine/core";
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+
+function toMiB(bytes: number): string {
+    return `${(bytes / 1024 ** 2).toFixed(1)} MB`;
+}
+
+export default function FileSelectionModal({
+    torrentId,
+    files: initialFiles,
+    resume = false,
+    onClose,
+}: {
+    // the modal is open while there is a torrent
+    torrentId: string | null;
+    // already known when the torrent was just added
+    files?: TorrentFile[];
+    // the torrent was added paused to pick its files first
+    resume?: boolean;
+    onClose: () => void;
+}) {
+    const { t } = useTranslation();
+
+    const [files, setFiles] = useState<TorrentFile[] | undefined>(undefined);
+    const [selected, setSelected] = useState<string[]>([]);
+
+    useEffect(() => {
+        if (!torrentId) return;
+        const request = initialFiles
+            ? Promise.resolve(initialFiles)
+            : getTorrentFiles(torrentId);
+        request
+            .then((v) => {
+                setFiles(v);
+                setSelected(
+                    v.filter((f) => f.selected).map((f) => String(f.index))
+                );
+            })
+            .catch((error) => {
+                console.error("Error getting torrent files:", error);
+                setFiles([]);
+            });
+    }, [torrentId, initialFiles]);
+
+    function close() {
+        setFiles(undefined);
+        onClose();
+    }
+
+    function save() {
+        if (!torrentId) return;
+        selectTorrentFiles(torrentId, selected.map(Number))
+            .then(() => resume && resumeTorrent(torrentId))
+            .catch((error) => {
+                console.error("Error selecting torrent files:", error);
+            })
+            .finally(close);
+    }
+
+    return (
+        <Modal
+            opened={torrentId !== null}
+            onClose={close}
+            title={t("torrent_files")}
+            size="xl"
+        >
+            {files === undefined ? (
+                <Loader />
+            ) : (
+                <div className="flex flex-col gap-3">
+                    <div className="flex flex-row gap-2">
+                        <Button
+                            variant="subtle"
+                            size="xs"
+                            onClick={() =>
+                                setSelected(files.map((f) => String(f.index)))
+                            }
+                        >
+                            {t("torrent_files_all")}
+                        </Button>
+                        <Button
+                            variant="subtle"
+                            size="xs"
+                            onClick={() => setSelected([])}
+                        >
+                            {t("torrent_files_none")}
+                        </Button>
+                    </div>
+                    <Checkbox.Group value={selected} onChange={setSelected}>
+                        <div className="flex flex-col gap-1">
+                            {files.map((file) => (
+                                <Checkbox
+                                    key={file.index}
+                                    value={String(file.index)}
+                                    label={`${file.name} (${toMiB(file.size)})`}
+                                />
+                            ))}
+                        </div>
+                    </Checkbox.Group>
+                    <Button onClick={save} disabled={selected.length === 0}>
+                        {resume ? t("torrent_files_download") : t("confirm")}
+                    </Button>
+                </div>
+            )}
+        </Modal>
+    );
+}
@@ -0,0 +1,88 @@
+import {
+    getSubscription,
+    removeSubscription,
+    setSubscription,
+} from "@/commands/commands";
+import type { Subscription } from "@/commands/types";
+import { Button, Modal, NumberInput, Switch } from "@mantine/core";
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+
+function defaultSubscription(animeId: number): Subscription {
+    return {
+        anime_id: animeId,
+        delay_hours: 6,
+        retry_hours: 72,
+    };
+}
+
+export default function SubscriptionModal({
+    animeId,
+    opened,
+    onClose,
+}: {
+    animeId: number;
+    opened: boolean;
+    onClose: () => void;
+}) {
+    const { t } = useTranslation();
+
+    const [enabled, setEnabled] = useState(false);
+    const [subscription, setLocalSubscription] = useState<Subscription>(
+        defaultSubscription(animeId)
+    );
+
+    useEffect(() => {
+        if (!opened) return;
+        getSubscription(animeId).then((v) => {
+            setEnabled(v !== null);
+            setLocalSubscription(v ?? defaultSubscription(animeId));
+        });
+    }, [animeId, opened]);
+
+    function update(values: Partial<Subscription>) {
+        setLocalSubscription((prev) => ({ ...prev, ...values }));
+    }
+
+    function save() {
+        const request = enabled
+            ? setSubscription(subscription)
+            : removeSubscription(animeId);
+        request
+            .catch((error) => {
+                console.error("Error saving subscription:", error);
+            })
+            .finally(onClose);
+    }
+
+    return (
+        <Modal opened={opened} onClose={onClose} title={t("subscription")}>
+            <div className="flex flex-col gap-3">
+                <Switch
+                    label={t("subscription_enabled")}
+                    checked={enabled}
+                    onChange={(e) => setEnabled(e.currentTarget.checked)}
+                />
+                <NumberInput
+                    label={t("subscription_delay")}
+                    min={0}
+                    value={subscription.delay_hours}
+                    onChange={(v) =>
+                        typeof v === "number" && update({ delay_hours: v })
+                    }
+                    disabled={!enabled}
+                />
+                <NumberInput
+                    label={t("subscription_retry")}
+                    min={1}
+                    value={subscription.retry_hours}
+                    onChange={(v) =>
+                        typeof v === "number" && update({ retry_hours: v })
+                    }
+                    disabled={!enabled}
+                />
+                <Button onClick={save}>{t("confirm")}</Button>
+            </div>
+        </Modal>
+    );
+}
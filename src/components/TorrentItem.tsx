@@ -1,6 +1,18 @@
-import type { ManagedTorrentInfo } from "@/commands/types";
+import { pauseTorrent, resumeTorrent } from "@/commands/commands";
+import type {
+    ManagedTorrentInfo,
+    TorrentControl,
+    TorrentSeeding,
+} from "@/commands/types";
 import { ActionIcon, Progress } from "@mantine/core";
-import { ArrowDown, ArrowUp, TvMinimalPlay } from "lucide-react";
+import {
+    ArrowDown,
+    ArrowUp,
+    ChevronsUp,
+    Pause,
+    Play,
+    TvMinimalPlay,
+} from "lucide-react";
 import { type MouseEvent, useCallback, useMemo } from "react";
 import { useTranslation } from "react-i18next";
 import { useNavigate } from "react-router";
@@ -10,12 +22,18 @@ export default function TorrentItem({
     ep,
     torrent,
     torrentId,
+    control,
+    seeding,
+    upgrade,
     onContextMenu,
 }: {
     animeName: string;
     ep: number;
     torrent: ManagedTorrentInfo;
     torrentId: string;
+    control: TorrentControl;
+    seeding: TorrentSeeding | null;
+    upgrade: boolean;
     onContextMenu: (e: MouseEvent<HTMLDivElement>) => void;
 }) {
     const { t } = useTranslation();
@@ -57,10 +75,37 @@ export default function TorrentItem({
         [animeName, ep, t]
     );
 
+    // paused by the download queue rather than by the user
+    const queued =
+        !torrent.stats.finished &&
+        torrent.stats.state === "paused" &&
+        !control.paused;
+
+    // the queue leaves finished torrents alone, their state is what counts
+    const paused = torrent.stats.finished
+        ? torrent.stats.state === "paused"
+        : control.paused;
+
+    const ratio = useMemo(() => {
+        if (!seeding || torrent.stats.total_bytes === 0) {
+            return undefined;
+        }
+        return (seeding.uploaded_bytes / torrent.stats.total_bytes).toFixed(2);
+    }, [seeding, torrent.stats.total_bytes]);
+
     function play() {
         navigate(`/play/${torrentId}`);
     }
 
+    function togglePaused() {
+        const request = paused
+            ? resumeTorrent(torrentId)
+            : pauseTorrent(torrentId);
+        request.catch((e) => {
+            console.error(e);
+        });
+    }
+
     return (
         <div
             className="flex flex-row justify-between rounded-lg shadow-sm p-2 m-1 hover:bg-gray-100 transition-all duration-200 select-none"
@@ -72,6 +117,27 @@ export default function TorrentItem({
                     <p className="text-sm rounded-full bg-cyan-200 px-1 py-0.5">
                         {epDisplay}
                     </p>
+                    {control.priority > 0 && (
+                        <ChevronsUp
+                            size={16}
+                            aria-label={t("torrent_priority_high")}
+                        />
+                    )}
+                    {upgrade && (
+                        <p className="text-sm rounded-full bg-violet-200 px-1 py-0.5">
+                            {t("torrent_upgrade")}
+                        </p>
+                    )}
+                    {queued && (
+                        <p className="text-sm rounded-full bg-gray-200 px-1 py-0.5">
+                            {t("torrent_queued")}
+                        </p>
+                    )}
+                    {paused && seeding?.stopped && (
+                        <p className="text-sm rounded-full bg-green-200 px-1 py-0.5">
+                            {t("torrent_seeding_stopped")}
+                        </p>
+                    )}
                 </div>
                 {!torrent.stats.finished && (
                     <Progress
@@ -107,12 +173,22 @@ export default function TorrentItem({
                         <p>Uploaded</p>
                         <p>{uploaded}</p>
                     </div>
+                    {ratio && <p>{t("torrent_ratio", { ratio })}</p>}
                     {torrent.stats.error && (
                         <p className="text-red-500">{torrent.stats.error}</p>
                     )}
                 </div>
             </div>
             <div className="flex flex-row items-center">
+                <ActionIcon
+                    variant="subtle"
+                    onClick={togglePaused}
+                    aria-label={
+                        paused ? t("torrent_resume") : t("torrent_pause")
+                    }
+                >
+                    {paused ? <Play /> : <Pause />}
+                </ActionIcon>
                 <ActionIcon variant="subtle" onClick={play}>
                     <TvMinimalPlay />
                 </ActionIcon>
@@ -1,65 +1,177 @@
-import { addTorrent, openUrl } from "@/commands/commands";
-import type { TorrentInfo } from "@/commands/types";
-import { ActionIcon, Table } from "@mantine/core";
-import { ArrowDownToLine, PanelTop } from "lucide-react";
+import {
+    addTorrent,
+    addTorrentForSelection,
+    openUrl,
+    replaceTorrent,
+} from "@/commands/commands";
+import type { TorrentFile, TorrentInfo } from "@/commands/types";
+import FileSelectionModal from "@/components/FileSelectionModal";
+import { ActionIcon, Checkbox, Table, Tooltip } from "@mantine/core";
+import { ArrowDownToLine, ListChecks, PanelTop } from "lucide-react";
+import { useMemo, useState } from "react";
 import { useTranslation } from "react-i18next";
 
+type SortKey = "score" | "size" | "date" | "seeders";
+
+function sortValue(torrent: TorrentInfo, key: SortKey): number {
+    switch (key) {
+        case "score":
+            return torrent.score?.value ?? 0;
+        case "size":
+            return torrent.size_bytes ?? 0;
+        case "date":
+            return torrent.timestamp ? Date.parse(torrent.timestamp) : 0;
+        case "seeders":
+            return torrent.seeders ?? 0;
+    }
+}
+
 export default function TorrentsTable({
     epId,
-    source,
     torrents,
+    replacing = false,
 }: {
     epId: number;
-    source: string;
     torrents: TorrentInfo[];
+    // the episode already has a torrent, new ones replace it once downloaded
+    replacing?: boolean;
 }) {
     const { t } = useTranslation();
 
+    const [deleteOld, setDeleteOld] = useState(true);
+    // a torrent added paused, waiting for its files to be chosen
+    const [selecting, setSelecting] = useState<
+        { torrentId: string; files: TorrentFile[] } | undefined
+    >(undefined);
+
+    const [sortKey, setSortKey] = useState<SortKey>("score");
+    // results arrive ranked, so sorting by score keeps the backend order for ties
+    const sortedTorrents = useMemo(
+        () =>
+            [...torrents].sort(
+                (a, b) => sortValue(b, sortKey) - sortValue(a, sortKey),
+            ),
+        [torrents, sortKey],
+    );
+
+    function sortableHeader(key: SortKey, label: string) {
+        return (
+            <Table.Th
+                className="cursor-pointer select-none"
+                onClick={() => setSortKey(key)}
+            >
+                {label}
+                {sortKey === key && " ▾"}
+            </Table.Th>
+        );
+    }
+
     function openUrlHandler(url: string) {
         openUrl(url);
     }
 
-    function addTorrentF(magnet: string) {
-        addTorrent(magnet, epId).catch((e) => {
+    function addTorrentF(torrent: TorrentInfo) {
+        const group = torrent.release.group ?? torrent.uploader ?? null;
+        const request = replacing
+            ? replaceTorrent(torrent.magnet, epId, deleteOld)
+            : addTorrent(torrent.magnet, epId, group);
+        request.catch((e) => {
             console.error(e);
         });
     }
 
+    function addTorrentWithSelection(torrent: TorrentInfo) {
+        const group = torrent.release.group ?? torrent.uploader ?? null;
+        addTorrentForSelection(torrent.magnet, epId, group)
+            .then(([torrentId, files]) => setSelecting({ torrentId, files }))
+            .catch((e) => {
+                console.error(e);
+            });
+    }
+
     return (
         <div className="flex flex-col items-start justify-start w-full px-2">
-            <h2 className="text-xl">{source}</h2>
+            <FileSelectionModal
+                torrentId={selecting?.torrentId ?? null}
+                files={selecting?.files}
+                resume
+                onClose={() => setSelecting(undefined)}
+            />
+            {replacing && (
+                <Checkbox
+                    className="py-2"
+                    label={t("torrent_replace_delete_old")}
+                    description={t("torrent_replace_description")}
+                    checked={deleteOld}
+                    onChange={(e) => setDeleteOld(e.currentTarget.checked)}
+                />
+            )}
             <Table>
                 <Table.Thead>
                     <Table.Tr>
+                        {sortableHeader("score", t("torrent_score"))}
                         <Table.Th>{t("torrent_name")}</Table.Th>
-                        <Table.Th>{t("torrent_size")}</Table.Th>
-                        <Table.Th>{t("torrent_date")}</Table.Th>
-                        <Table.Th>{t("torrent_seeders")}</Table.Th>
+                        <Table.Th>{t("torrent_group")}</Table.Th>
+                        <Table.Th>{t("torrent_resolution")}</Table.Th>
+                        {sortableHeader("size", t("torrent_size"))}
+                        {sortableHeader("date", t("torrent_date"))}
+                        {sortableHeader("seeders", t("torrent_seeders"))}
                         <Table.Th>{t("torrent_leechers")}</Table.Th>
                         <Table.Th>{t("torrent_uploader")}</Table.Th>
+                        <Table.Th>{t("torrent_source")}</Table.Th>
                         <Table.Th>{t("torrent_actions")}</Table.Th>
                     </Table.Tr>
                 </Table.Thead>
                 <Table.Tbody>
-                    {torrents.map((torrent) => (
+                    {sortedTorrents.map((torrent) => (
                         <Table.Tr key={torrent.magnet}>
+                            <Table.Td>
+                                <Tooltip
+                                    label={torrent.score?.reasons.join(", ")}
+                                    disabled={!torrent.score?.reasons.length}
+                                    multiline
+                                >
+                                    <span>{torrent.score?.value}</span>
+                                </Tooltip>
+                            </Table.Td>
                             <Table.Td>{torrent.name}</Table.Td>
+                            <Table.Td>{torrent.release.group}</Table.Td>
+                            <Table.Td>
+                                {torrent.release.resolution &&
+                                    `${torrent.release.resolution}p`}
+                            </Table.Td>
                             <Table.Td>{torrent.size}</Table.Td>
                             <Table.Td>{torrent.date}</Table.Td>
                             <Table.Td>{torrent.seeders}</Table.Td>
                             <Table.Td>{torrent.leechers}</Table.Td>
                             <Table.Td>{torrent.uploader}</Table.Td>
+                            <Table.Td>{torrent.source}</Table.Td>
                             <Table.Td>
                                 <div className="flex flex-row gap-2">
                                     <ActionIcon.Group>
                                         <ActionIcon
                                             variant="outline"
                                             onClick={() =>
-                                                addTorrentF(torrent.magnet)
+                                                addTorrentF(torrent)
                                             }
                                         >
                                             <ArrowDownToLine />
                                         </ActionIcon>
+                                        {!replacing && (
+                                            <ActionIcon
+                                                variant="outline"
+                                                aria-label={t(
+                                                    "torrent_add_select_files"
+                                                )}
+                                                onClick={() =>
+                                                    addTorrentWithSelection(
+                                                        torrent
+                                                    )
+                                                }
+                                            >
+                                                <ListChecks />
+                                            </ActionIcon>
+                                        )}
                                         {torrent.url !== null && (
                                             <ActionIcon
                                                 variant="outline"
@@ -3,18 +3,30 @@ import {
     getEpisodes,
     getLastWatchedEp,
     initSearchTorrents,
+    searchTorrentsPage,
 } from "@/commands/commands";
 import type {
     AnimeSearchResultItem,
     Episode,
+    SourceResult,
     TorrentInfo,
 } from "@/commands/types";
 import AnimeSummary from "@/components/AnimeSummary";
 import EpisodeItem from "@/components/EpisodeItem";
 import TorrentsTable from "@/components/TorrentsTable";
 import { useCurrentTitle } from "@/states";
-import { Card, Drawer, Loader } from "@mantine/core";
+import {
+    ActionIcon,
+    Alert,
+    Button,
+    Card,
+    Drawer,
+    Loader,
+    TextInput,
+    Tooltip,
+} from "@mantine/core";
 import { useDisclosure } from "@mantine/hooks";
+import { RefreshCw } from "lucide-react";
 import { useCallback, useEffect, useState } from "react";
 import { useTranslation } from "react-i18next";
 import { useParams } from "react-router";
@@ -57,25 +69,69 @@ export default function AddedAnime() {
     }, [animeId, fetchAnimeInfo]);
 
     const [searchingTorrents, setSearchingTorrents] = useState(false);
-    const [torrentResults, setTorrentResults] = useState<
-        Partial<{
-            [x: string]: TorrentInfo[];
-        }>
-    >({});
+    const [torrentResults, setTorrentResults] = useState<TorrentInfo[]>([]);
+    const [failedSources, setFailedSources] = useState<SourceResult[]>([]);
     const [torrentEpId, setTorrentEpId] = useState<number | null>(null);
-    function searchTorrents(epId: number) {
+    const [torrentQuery, setTorrentQuery] = useState("");
+    // last loaded page of every source that may have more results
+    const [sourcePages, setSourcePages] = useState<Record<string, number>>({});
+    const [loadingSource, setLoadingSource] = useState<string | null>(null);
+    // later pages of a refreshed search skip the cache too
+    const [refreshed, setRefreshed] = useState(false);
+    function searchTorrents(
+        epId: number,
+        query?: string,
+        forceRefresh?: boolean,
+    ) {
         setSearchingTorrents(true);
         setTorrentEpId(epId);
+        setRefreshed(forceRefresh ?? false);
         open();
-        initSearchTorrents(epId)
+        initSearchTorrents(epId, query, forceRefresh)
             .then((v) => {
-                setTorrentResults(v);
+                setTorrentResults(v.torrents);
+                setFailedSources(v.sources.filter((s) => s.error));
+                setSourcePages(
+                    Object.fromEntries(
+                        v.sources
+                            .filter((s) => s.torrents > 0)
+                            .map((s) => [s.source, 1]),
+                    ),
+                );
             })
             .finally(() => {
                 setSearchingTorrents(false);
             });
     }
 
+    function loadMoreTorrents(source: string) {
+        const page = sourcePages[source] + 1;
+        setLoadingSource(source);
+        searchTorrentsPage(
+            torrentEpId!,
+            source,
+            page,
+            torrentQuery.trim() || undefined,
+            refreshed,
+        )
+            .then((v) => {
+                const known = new Set(torrentResults.map((t) => t.magnet));
+                setTorrentResults([
+                    ...torrentResults,
+                    ...v.torrents.filter((t) => !known.has(t.magnet)),
+                ]);
+                const { [source]: _, ...rest } = sourcePages;
+                setSourcePages(
+                    v.sources[0]?.torrents > 0
+                        ? { ...rest, [source]: page }
+                        : rest,
+                );
+            })
+            .finally(() => {
+                setLoadingSource(null);
+            });
+    }
+
     return (
         <>
             <div className="flex flex-col justify-start items-start w-full h-full px-4 gap-4">
@@ -99,9 +155,10 @@ export default function AddedAnime() {
                                     <EpisodeItem
                                         ep={ep}
                                         key={ep.id}
-                                        onClickMagnet={() =>
-                                            searchTorrents(ep.id)
-                                        }
+                                        onClickMagnet={() => {
+                                            setTorrentQuery("");
+                                            searchTorrents(ep.id);
+                                        }}
                                         isLastWatched={
                                             lastWatchedEpId === ep.id
                                         }
@@ -129,24 +186,77 @@ export default function AddedAnime() {
                         <Loader size="lg" />
                     </div>
                 ) : (
-                    <div>
-                        {Object.keys(torrentResults).length > 0 ? (
-                            Object.entries(torrentResults).map(
-                                ([sourceName, torrents]) => {
-                                    if (torrents === undefined) return null;
-                                    return (
-                                        <TorrentsTable
-                                            epId={torrentEpId!}
-                                            source={sourceName}
-                                            torrents={torrents}
-                                            key={sourceName}
-                                        />
-                                    );
+                    <div className="flex flex-col gap-2">
+                        <div className="flex flex-row gap-2">
+                            <TextInput
+                                className="flex-1"
+                                placeholder={t("search_query_placeholder")}
+                                value={torrentQuery}
+                                onChange={(e) =>
+                                    setTorrentQuery(e.currentTarget.value)
+                                }
+                            />
+                            <Button
+                                onClick={() =>
+                                    searchTorrents(
+                                        torrentEpId!,
+                                        torrentQuery.trim() || undefined,
+                                    )
+                                }
+                            >
+                                {t("search")}
+                            </Button>
+                            <Tooltip label={t("search_refresh")}>
+                                <ActionIcon
+                                    variant="outline"
+                                    size="lg"
+                                    onClick={() =>
+                                        searchTorrents(
+                                            torrentEpId!,
+                                            torrentQuery.trim() || undefined,
+                                            true,
+                                        )
+                                    }
+                                >
+                                    <RefreshCw />
+                                </ActionIcon>
+                            </Tooltip>
+                        </div>
+                        {failedSources.map((source) => (
+                            <Alert
+                                color="yellow"
+                                key={source.source}
+                                title={t("search_source_failed", {
+                                    source: source.source,
+                                })}
+                            >
+                                {source.error}
+                            </Alert>
+                        ))}
+                        {torrentResults.length > 0 ? (
+                            <TorrentsTable
+                                epId={torrentEpId!}
+                                torrents={torrentResults}
+                                replacing={
+                                    episodes.find((ep) => ep.id === torrentEpId)
+                                        ?.torrent_id != null
                                 }
-                            )
+                            />
                         ) : (
                             <div>{t("search_results_none")}</div>
                         )}
+                        <div className="flex flex-row flex-wrap gap-2">
+                            {Object.keys(sourcePages).map((source) => (
+                                <Button
+                                    variant="light"
+                                    key={source}
+                                    loading={loadingSource === source}
+                                    onClick={() => loadMoreTorrents(source)}
+                                >
+                                    {t("search_more_from", { source })}
+                                </Button>
+                            ))}
+                        </div>
                     </div>
                 )}
             </Drawer>
@@ -1,20 +1,62 @@
-import { getTorrentStats, removeTorrent as rT } from "@/commands/commands";
-import type { TorrentStat } from "@/commands/types";
+import {
+    getTorrentStats,
+    removeTorrent as rT,
+    setTorrentPriority,
+} from "@/commands/commands";
+import type {
+    TorrentProgress,
+    TorrentProgressEntry,
+    TorrentStat,
+} from "@/commands/types";
+import FileSelectionModal from "@/components/FileSelectionModal";
 import TorrentItem from "@/components/TorrentItem";
 import { Collapse, Modal, Button } from "@mantine/core";
 import { useDisclosure } from "@mantine/hooks";
 import { ChevronDown, ChevronRight } from "lucide-react";
-import { useEffect, useMemo, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import { Item, Menu, useContextMenu } from "react-contexify";
 import "react-contexify/dist/ReactContexify.css";
 import { useTranslation } from "react-i18next";
 
 const CONTEXT_MENU_ID = "torrent-context-menu";
 
+function speed(mbps: number) {
+    return { mbps, human_readable: `${mbps.toFixed(2)} MiB/s` };
+}
+
+// updates the numbers of a torrent whose state has not changed
+function applyProgress(
+    torrent: TorrentStat,
+    progress: TorrentProgressEntry
+): TorrentStat {
+    const stats = torrent.info.stats;
+    return {
+        ...torrent,
+        info: {
+            ...torrent.info,
+            stats: {
+                ...stats,
+                error: progress.error,
+                progress_bytes: progress.progress_bytes,
+                uploaded_bytes: progress.uploaded_bytes,
+                total_bytes: progress.total_bytes,
+                live: stats.live && {
+                    ...stats.live,
+                    download_speed: speed(progress.download_speed),
+                    upload_speed: speed(progress.upload_speed),
+                },
+            },
+        },
+    };
+}
+
 export default function Local() {
     const { t } = useTranslation();
 
     const [torrents, setTorrents] = useState<TorrentStat[]>([]);
+    const torrentsRef = useRef<TorrentStat[]>([]);
+    torrentsRef.current = torrents;
 
     const { show: showContextMenu } = useContextMenu({
         id: CONTEXT_MENU_ID,
@@ -41,28 +83,78 @@ export default function Local() {
 
     const [completedOpen, { toggle: toggleCompleted }] = useDisclosure(true);
 
-    useEffect(() => {
+    const refresh = useCallback(() => {
         getTorrentStats().then((torrents) => {
             setTorrents(torrents);
         });
+    }, []);
 
-        const interval = setInterval(() => {
-            getTorrentStats().then((torrents) => {
-                setTorrents(torrents);
-            });
-        }, 1000);
+    useEffect(() => {
+        refresh();
+
+        const unlistenProgress = listen<TorrentProgress>(
+            "torrent-progress",
+            (e) => {
+                const progress = new Map(
+                    e.payload.torrents.map((p) => [p.id, p])
+                );
+                const current = torrentsRef.current;
+                // new torrents and state changes need the full stats again
+                const known = new Set(current.map((t) => t.torrent_id));
+                const stale =
+                    e.payload.torrents.some(
+                        (p) => p.ep !== null && !known.has(p.id)
+                    ) ||
+                    current.some((t) => {
+                        const p = progress.get(t.torrent_id);
+                        return (
+                            p !== undefined &&
+                            (p.state !== t.info.stats.state ||
+                                p.finished !== t.info.stats.finished)
+                        );
+                    });
+                if (stale) {
+                    refresh();
+                    return;
+                }
+                setTorrents((prev) =>
+                    prev.map((t) => {
+                        const p = progress.get(t.torrent_id);
+                        return p ? applyProgress(t, p) : t;
+                    })
+                );
+            }
+        );
+        const unlistenRemoved = listen<{ id: string }>(
+            "torrent-removed",
+            (e) => {
+                setTorrents((prev) =>
+                    prev.filter((t) => t.torrent_id !== e.payload.id)
+                );
+            }
+        );
 
         return () => {
-            clearInterval(interval);
+            unlistenProgress.then((f) => f());
+            unlistenRemoved.then((f) => f());
         };
-    }, []);
+    }, [refresh]);
 
     const [removeTorrentId, setRemoveTorrentId] = useState<string | null>(null);
+    const [filesTorrentId, setFilesTorrentId] = useState<string | null>(null);
     function removeTorrent(torrentId: string) {
         setRemoveTorrentId(torrentId);
         openModal();
     }
 
+    function setPriority(torrentId: string, priority: number) {
+        setTorrentPriority(torrentId, priority)
+            .then(refresh)
+            .catch((e) => {
+                console.error(e);
+            });
+    }
+
     function doRemoveTorrent() {
         if (removeTorrentId) {
             rT(removeTorrentId)
@@ -115,6 +207,9 @@ export default function Local() {
                                 animeName={torrent.anime_name}
                                 ep={torrent.ep}
                                 torrentId={torrent.torrent_id}
+                                control={torrent.control}
+                                seeding={torrent.seeding}
+                                upgrade={torrent.upgrade}
                                 onContextMenu={(e) =>
                                     handleTorrentContextMenu(
                                         torrent.torrent_id,
@@ -145,6 +240,9 @@ export default function Local() {
                                 animeName={torrent.anime_name}
                                 ep={torrent.ep}
                                 torrentId={torrent.torrent_id}
+                                control={torrent.control}
+                                seeding={torrent.seeding}
+                                upgrade={torrent.upgrade}
                                 onContextMenu={(e) =>
                                     handleTorrentContextMenu(
                                         torrent.torrent_id,
@@ -183,7 +281,24 @@ export default function Local() {
                 </div>
             </Modal>
 
+            <FileSelectionModal
+                torrentId={filesTorrentId}
+                onClose={() => setFilesTorrentId(null)}
+            />
+
             <Menu id={CONTEXT_MENU_ID}>
+                <Item onClick={() => setPriority(removeTorrentId!, 1)}>
+                    {t("torrent_priority_high")}
+                </Item>
+                <Item onClick={() => setPriority(removeTorrentId!, 0)}>
+                    {t("torrent_priority_normal")}
+                </Item>
+                <Item onClick={() => setPriority(removeTorrentId!, -1)}>
+                    {t("torrent_priority_low")}
+                </Item>
+                <Item onClick={() => setFilesTorrentId(removeTorrentId)}>
+                    {t("torrent_files")}
+                </Item>
                 <Item onClick={() => removeTorrent(removeTorrentId!)}>
                     {t("torrent_remove")}
                 </Item>
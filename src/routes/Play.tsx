@@ -8,6 +8,7 @@ import { useCurrentTitle } from "@/states";
 import { ActionIcon, Select, Slider } from "@mantine/core";
 import {
     Fullscreen,
+    Link,
     Pause,
     PlayIcon,
     RotateCcw,
@@ -16,7 +17,7 @@ import {
     VolumeX,
 } from "lucide-react";
 import { useEffect, useMemo, useRef, useState } from "react";
-import { useParams } from "react-router";
+import { useParams, useSearchParams } from "react-router";
 import cn from "classnames";
 import { useTranslation } from "react-i18next";
 import { convertFileSrc } from "@tauri-apps/api/core";
@@ -28,9 +29,12 @@ export default function Play() {
     const trackRef = useRef<HTMLTrackElement | null>(null);
     const videoContainerRef = useRef<HTMLDivElement | null>(null);
     const params = useParams();
+    const [searchParams] = useSearchParams();
     const setTitle = useCurrentTitle((state) => state.updateTitle);
     const title = useCurrentTitle((state) => state.title);
     const [videoSrc, setVideoSrc] = useState<string | null>(null);
+    // set while the video is still downloading, for external players too
+    const [streamUrl, setStreamUrl] = useState<string | null>(null);
     const [trackList, setTrackList] = useState<string[]>([]);
     const [track, setTrack] = useState<string | null>(null);
     const [epId, setEpId] = useState<number | null>(null);
@@ -70,10 +74,15 @@ export default function Play() {
     // 加载剧集播放信息，设置视频源、字幕、标题，并在canplay时跳转到上次进度
     useEffect(() => {
         if (params.torrentId === undefined) return;
-        parseTorrentPlayInfo(params.torrentId).then((info) => {
-            const src = convertFileSrc(info.video);
+        const ep = searchParams.get("ep");
+        parseTorrentPlayInfo(
+            params.torrentId,
+            ep === null ? undefined : Number(ep)
+        ).then((info) => {
+            const src = info.stream_url ?? convertFileSrc(info.video);
             setEpId(info.ep.id);
             setVideoSrc(src);
+            setStreamUrl(info.stream_url ?? null);
             setTrackList(info.subtitles);
             const displayTitle = `${info.anime.name_cn} ${t("episode_num", {
                 num: info.ep.ep ?? info.ep.sort,
@@ -88,7 +97,7 @@ export default function Play() {
                 { once: true }
             );
         });
-    }, [params.torrentId, setTitle, t]);
+    }, [params.torrentId, searchParams, setTitle, t]);
 
     // 处理播放状态、进度、全屏、快捷键、鼠标移动等事件监听和清理
     useEffect(() => {
@@ -102,7 +111,11 @@ export default function Play() {
             if (videoRef.current) {
                 setProgress(videoRef.current.currentTime);
                 if (epId) {
-                    sP(epId, videoRef.current.currentTime);
+                    sP(
+                        epId,
+                        videoRef.current.currentTime,
+                        videoRef.current.duration
+                    );
                 }
             }
         };
@@ -481,6 +494,25 @@ export default function Play() {
                     </ActionIcon>
                 </div>
                 <div className="flex-1 flex flex-row items-center justify-end">
+                    {streamUrl && (
+                        <ActionIcon
+                            size={32}
+                            variant="subtle"
+                            title={t("play_copy_stream_url")}
+                            onClick={() => {
+                                navigator.clipboard
+                                    .writeText(streamUrl)
+                                    .catch((error) => {
+                                        console.error(
+                                            "Error copying stream URL:",
+                                            error
+                                        );
+                                    });
+                            }}
+                        >
+                            <Link size={24} />
+                        </ActionIcon>
+                    )}
                     <ActionIcon
                         size={32}
                         variant="subtle"
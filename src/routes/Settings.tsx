@@ -2,12 +2,39 @@ import {
     getConfig,
     selectDownloadPath,
     setBangumiProxy,
+    setDiskReserve,
     setLogLevel,
+    setMaxActiveDownloads,
+    setNyaaConfig,
+    setSearchCacheTtl,
+    setRetentionPolicy,
+    setSeedingPolicy,
     setTorrentsProxy,
 } from "@/commands/commands";
-import type { Config, LogLevelFilter } from "@/commands/types";
+import type {
+    Config,
+    LogLevelFilter,
+    NyaaCategory,
+    NyaaConfig,
+    NyaaFilter,
+    RelocationProgress,
+    RetentionPolicy,
+    SeedingPolicy,
+} from "@/commands/types";
+import BandwidthModal from "@/components/BandwidthModal";
+import CleanupPreviewModal from "@/components/CleanupPreviewModal";
 import { useCurrentTitle } from "@/states";
-import { Button, Input, Select, Switch, TableOfContents } from "@mantine/core";
+import {
+    Button,
+    Input,
+    NumberInput,
+    Progress,
+    Select,
+    Switch,
+    TableOfContents,
+} from "@mantine/core";
+import { useDisclosure } from "@mantine/hooks";
+import { listen } from "@tauri-apps/api/event";
 import { useEffect, useMemo, useState } from "react";
 import { useTranslation } from "react-i18next";
 
@@ -29,6 +56,15 @@ export default function Settings() {
     const [trsProxyTmp, setTrsProxyTmp] = useState<string | undefined>(
         undefined
     );
+    const [nyaaUrlTmp, setNyaaUrlTmp] = useState<string | undefined>(
+        undefined
+    );
+    const [bandwidthOpened, bandwidth] = useDisclosure(false);
+    const [cleanupPreviewOpened, cleanupPreview] = useDisclosure(false);
+    // set while the downloads move to a new download path
+    const [relocation, setRelocation] = useState<RelocationProgress | null>(
+        null
+    );
 
     const logLevels = useMemo(() => {
         return ["error", "warn", "info", "debug", "trace"].map((v, i) => {
@@ -39,6 +75,29 @@ export default function Settings() {
         });
     }, [t]);
 
+    const nyaaCategories = useMemo(() => {
+        return [
+            "all_anime",
+            "english_translated",
+            "non_english_translated",
+            "raw",
+        ].map((v) => {
+            return {
+                value: v,
+                label: t(`nyaa_category.${v}`),
+            };
+        });
+    }, [t]);
+
+    const nyaaFilters = useMemo(() => {
+        return ["no_filter", "no_remakes", "trusted_only"].map((v) => {
+            return {
+                value: v,
+                label: t(`nyaa_filter.${v}`),
+            };
+        });
+    }, [t]);
+
     useEffect(() => {
         setTitle(t("settings_title"));
         getConfig().then((v) => {
@@ -49,6 +108,7 @@ export default function Settings() {
     useEffect(() => {
         setBgmProxyTmp(config?.network_config.bgm_proxy);
         setTrsProxyTmp(config?.network_config.torrents_proxy);
+        setNyaaUrlTmp(config?.search_config.nyaa.base_url);
     }, [config]);
 
     function changeLanguage(lang: string | null) {
@@ -86,12 +146,91 @@ export default function Settings() {
         });
     }
 
-    function chooseDownloadDirectory() {
-        selectDownloadPath().then((c) => {
+    function updateNyaaConfig(nyaa: Partial<NyaaConfig>) {
+        if (!config) return;
+        setNyaaConfig({ ...config.search_config.nyaa, ...nyaa }).then((c) => {
+            setConfig(c);
+        });
+    }
+
+    function setNyaaUrl() {
+        if (!nyaaUrlTmp) return;
+        updateNyaaConfig({ base_url: nyaaUrlTmp });
+    }
+
+    function setCacheTtl(minutes: number | string) {
+        if (typeof minutes !== "number") return;
+        setSearchCacheTtl(minutes * 60).then((c) => {
+            setConfig(c);
+        });
+    }
+
+    function setMaxActive(max: number | string) {
+        if (typeof max !== "number") return;
+        setMaxActiveDownloads(max).then((c) => {
+            setConfig(c);
+        });
+    }
+
+    function setReserve(reserve: number | string) {
+        if (typeof reserve !== "number") return;
+        setDiskReserve(reserve).then((c) => {
+            setConfig(c);
+        });
+    }
+
+    function updateSeedingPolicy(policy: Partial<SeedingPolicy>) {
+        if (!config) return;
+        setSeedingPolicy({
+            ...config.download_config.seeding_policy,
+            ...policy,
+        }).then((c) => {
             setConfig(c);
         });
     }
 
+    // an emptied input removes the limit, partial input is ignored
+    function setSeedingLimit(
+        key: keyof SeedingPolicy,
+        value: number | string
+    ) {
+        if (value === "") {
+            updateSeedingPolicy({ [key]: null });
+        } else if (typeof value === "number") {
+            updateSeedingPolicy({ [key]: value });
+        }
+    }
+
+    function updateRetentionPolicy(policy: Partial<RetentionPolicy>) {
+        if (!config) return;
+        setRetentionPolicy({
+            ...config.download_config.retention_policy,
+            ...policy,
+        }).then((c) => {
+            setConfig(c);
+        });
+    }
+
+    function chooseDownloadDirectory() {
+        const unlisten = listen<RelocationProgress>(
+            "relocation-progress",
+            (e) => {
+                setRelocation(e.payload);
+            }
+        );
+        selectDownloadPath()
+            .then((c) => {
+                setConfig(c);
+            })
+            .catch((error) => {
+                console.error("Error moving downloads:", error);
+            })
+            .finally(() => {
+                unlisten.then((f) => f());
+                setRelocation(null);
+            });
+    }
+
     function setLLevel(level: LogLevelFilter) {
         setLogLevel(level).then((c) => {
             setConfig(c);
@@ -185,6 +324,62 @@ export default function Settings() {
                         </div>
                     </div>
                 </div>
+                <div className="flex flex-col justify-start items-start gap-1">
+                    <h2 className="text-2xl font-bold mb-2">
+                        {t("settings_search")}
+                    </h2>
+                    <div className="flex flex-col justify-start items-start gap-2">
+                        <div className="flex flex-row items-center gap-2">
+                            <span>{t("settings_search_nyaa_url")}</span>
+                            <Input
+                                placeholder="https://nyaa.si"
+                                value={nyaaUrlTmp}
+                                onChange={(e) =>
+                                    setNyaaUrlTmp(e.currentTarget.value)
+                                }
+                                onBlur={setNyaaUrl}
+                                onKeyDown={(e) => {
+                                    if (e.key === "Enter") {
+                                        setNyaaUrl();
+                                    }
+                                }}
+                            />
+                        </div>
+                        <div className="flex flex-row items-center gap-2">
+                            <span>{t("settings_search_nyaa_category")}</span>
+                            <Select
+                                value={config?.search_config.nyaa.category}
+                                onChange={(e) =>
+                                    updateNyaaConfig({
+                                        category: e as NyaaCategory,
+                                    })
+                                }
+                                data={nyaaCategories}
+                            />
+                        </div>
+                        <div className="flex flex-row items-center gap-2">
+                            <span>{t("settings_search_nyaa_filter")}</span>
+                            <Select
+                                value={config?.search_config.nyaa.filter}
+                                onChange={(e) =>
+                                    updateNyaaConfig({ filter: e as NyaaFilter })
+                                }
+                                data={nyaaFilters}
+                            />
+                        </div>
+                        <div className="flex flex-row items-center gap-2">
+                            <span>{t("settings_search_cache_ttl")}</span>
+                            <NumberInput
+                                min={0}
+                                value={
+                                    config &&
+                                    config.search_config.cache_ttl_secs / 60
+                                }
+                                onChange={setCacheTtl}
+                            />
+                        </div>
+                    </div>
+                </div>
                 <div className="flex flex-col justify-start items-start gap-1">
                     <h2 className="text-2xl font-bold mb-2">
                         {t("settings_download")}
@@ -195,10 +390,173 @@ export default function Settings() {
                             <Button
                                 onClick={chooseDownloadDirectory}
                                 variant="outline"
+                                loading={relocation !== null}
                             >
                                 {config?.download_config.download_path}
                             </Button>
                         </div>
+                        {relocation && (
+                            <div className="flex flex-col gap-1 w-full">
+                                <span className="text-sm text-gray-500">
+                                    {t("settings_download_path_moving")}
+                                </span>
+                                <Progress
+                                    value={
+                                        relocation.total_bytes === 0
+                                            ? 100
+                                            : (relocation.moved_bytes /
+                                                  relocation.total_bytes) *
+                                              100
+                                    }
+                                />
+                            </div>
+                        )}
+                        <div className="flex flex-row items-center gap-2">
+                            <span>{t("settings_max_active_downloads")}</span>
+                            <NumberInput
+                                min={0}
+                                value={
+                                    config?.download_config.max_active_downloads
+                                }
+                                onChange={setMaxActive}
+                                description={t(
+                                    "settings_max_active_downloads_description"
+                                )}
+                            />
+                        </div>
+                        <div className="flex flex-row items-center gap-2">
+                            <span>{t("settings_disk_reserve")}</span>
+                            <NumberInput
+                                min={0}
+                                suffix=" GB"
+                                value={
+                                    config?.download_config.disk_reserve_gib
+                                }
+                                onChange={setReserve}
+                                description={t(
+                                    "settings_disk_reserve_description"
+                                )}
+                            />
+                        </div>
+                        <div className="flex flex-row items-center gap-2">
+                            <span>{t("bandwidth")}</span>
+                            <Button onClick={bandwidth.open} variant="outline">
+                                {t("settings_bandwidth_edit")}
+                            </Button>
+                        </div>
+                        <div className="flex flex-row items-center gap-2">
+                            <span>{t("seeding_max_ratio")}</span>
+                            <NumberInput
+                                min={0}
+                                step={0.1}
+                                decimalScale={2}
+                                placeholder={t("seeding_unlimited")}
+                                value={
+                                    config?.download_config.seeding_policy
+                                        .max_ratio ?? ""
+                                }
+                                onChange={(v) =>
+                                    setSeedingLimit("max_ratio", v)
+                                }
+                            />
+                        </div>
+                        <div className="flex flex-row items-center gap-2">
+                            <span>{t("seeding_max_hours")}</span>
+                            <NumberInput
+                                min={0}
+                                allowDecimal={false}
+                                placeholder={t("seeding_unlimited")}
+                                value={
+                                    config?.download_config.seeding_policy
+                                        .max_hours ?? ""
+                                }
+                                onChange={(v) =>
+                                    setSeedingLimit("max_hours", v)
+                                }
+                            />
+                        </div>
+                    </div>
+                </div>
+                <div className="flex flex-col justify-start items-start gap-1">
+                    <h2 className="text-2xl font-bold mb-2">
+                        {t("settings_cleanup")}
+                    </h2>
+                    <div className="flex flex-col justify-start items-start gap-2">
+                        <div className="flex flex-row items-center gap-2">
+                            <span>{t("settings_cleanup_enabled")}</span>
+                            <Switch
+                                checked={
+                                    config?.download_config.retention_policy
+                                        .enabled
+                                }
+                                onChange={(e) =>
+                                    updateRetentionPolicy({
+                                        enabled: e.currentTarget.checked,
+                                    })
+                                }
+                            />
+                            <Button
+                                onClick={cleanupPreview.open}
+                                variant="outline"
+                            >
+                                {t("cleanup_preview")}
+                            </Button>
+                        </div>
+                        <div className="flex flex-row items-center gap-2">
+                            <span>{t("settings_cleanup_watched_percent")}</span>
+                            <NumberInput
+                                min={1}
+                                max={100}
+                                allowDecimal={false}
+                                suffix="%"
+                                value={
+                                    config?.download_config.retention_policy
+                                        .watched_percent
+                                }
+                                onChange={(v) =>
+                                    typeof v === "number" &&
+                                    updateRetentionPolicy({
+                                        watched_percent: v,
+                                    })
+                                }
+                            />
+                        </div>
+                        <div className="flex flex-row items-center gap-2">
+                            <span>{t("settings_cleanup_grace_hours")}</span>
+                            <NumberInput
+                                min={0}
+                                allowDecimal={false}
+                                value={
+                                    config?.download_config.retention_policy
+                                        .grace_hours
+                                }
+                                onChange={(v) =>
+                                    typeof v === "number" &&
+                                    updateRetentionPolicy({ grace_hours: v })
+                                }
+                            />
+                        </div>
+                        <div className="flex flex-row items-center gap-2">
+                            <span>{t("settings_cleanup_quota")}</span>
+                            <NumberInput
+                                min={1}
+                                allowDecimal={false}
+                                placeholder={t("settings_cleanup_no_quota")}
+                                value={
+                                    config?.download_config.retention_policy
+                                        .quota_gib ?? ""
+                                }
+                                onChange={(v) =>
+                                    updateRetentionPolicy({
+                                        quota_gib:
+                                            typeof v === "number" ? v : null,
+                                    })
+                                }
+                                description={t(
+                                    "settings_cleanup_quota_description"
+                                )}
+                            />
+                        </div>
                     </div>
                 </div>
                 <div className="flex flex-col justify-start items-start gap-1">
@@ -217,6 +575,18 @@ export default function Settings() {
                     </div>
                 </div>
             </div>
+            <CleanupPreviewModal
+                opened={cleanupPreviewOpened}
+                onClose={cleanupPreview.close}
+            />
+            {config && (
+                <BandwidthModal
+                    config={config.download_config}
+                    opened={bandwidthOpened}
+                    onClose={bandwidth.close}
+                    onSaved={setConfig}
+                />
+            )}
             <div className="w-[200px]">
                 <TableOfContents
                     scrollSpyOptions={{